@@ -0,0 +1,157 @@
+use core::fmt;
+
+use crate::Error;
+
+/// Limits on the amount of work performed by [`sieve_and_find_with_budget`](`crate::sieve_and_find_with_budget`)
+/// and the `*_with_budget` functions built on top of it.
+///
+/// When any of the limits is reached, the search stops with [`Error::AttemptsExhausted`],
+/// reporting the number of candidates tested.
+/// By default, the budget is unlimited.
+///
+/// The functions performing several searches (e.g. for auxiliary primes) apply the budget to each of them separately.
+///
+/// FIPS-186.5[^FIPS], Appendix A.1.3 requires the number of candidates tested during the prime generation
+/// to be limited (e.g. to `5 * bit_length` for the primes of an RSA modulus);
+/// [`with_max_candidates`](`Self::with_max_candidates`) can be used for that.
+///
+/// [^FIPS]: FIPS-186.5 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf>
+#[derive(Clone, Copy, Default)]
+pub struct Budget<'a> {
+    max_candidates: Option<usize>,
+    max_sieves: Option<usize>,
+    deadline: Option<&'a (dyn Fn() -> bool + Sync)>,
+}
+
+impl<'a> Budget<'a> {
+    /// Creates an unlimited budget.
+    pub const fn unlimited() -> Self {
+        Self {
+            max_candidates: None,
+            max_sieves: None,
+            deadline: None,
+        }
+    }
+
+    /// Limits the number of candidates passed to the predicate.
+    pub const fn with_max_candidates(self, max_candidates: usize) -> Self {
+        Self {
+            max_candidates: Some(max_candidates),
+            ..self
+        }
+    }
+
+    /// Limits the number of sieves created by the sieve factory (including the first one).
+    pub const fn with_max_sieves(self, max_sieves: usize) -> Self {
+        Self {
+            max_sieves: Some(max_sieves),
+            ..self
+        }
+    }
+
+    /// Sets a closure that returns `true` when the time allotted to the search is up.
+    ///
+    /// It is called before every candidate is tested and before every sieve is created,
+    /// so it should be cheap compared to a primality test.
+    pub const fn with_deadline(self, deadline: &'a (dyn Fn() -> bool + Sync)) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+}
+
+impl fmt::Debug for Budget<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Budget")
+            .field("max_candidates", &self.max_candidates)
+            .field("max_sieves", &self.max_sieves)
+            .field("deadline", &self.deadline.map(|_| "<closure>"))
+            .finish()
+    }
+}
+
+/// Keeps track of the work performed against a [`Budget`].
+#[derive(Debug)]
+pub(crate) struct BudgetTracker<'a> {
+    budget: Budget<'a>,
+    candidates: usize,
+    sieves: usize,
+}
+
+impl<'a> BudgetTracker<'a> {
+    pub fn new(budget: Budget<'a>) -> Self {
+        Self {
+            budget,
+            candidates: 0,
+            sieves: 0,
+        }
+    }
+
+    fn exhausted(&self) -> Error {
+        Error::AttemptsExhausted {
+            attempts: self.candidates,
+        }
+    }
+
+    fn deadline_passed(&self) -> bool {
+        self.budget.deadline.is_some_and(|deadline| deadline())
+    }
+
+    /// Registers the creation of a new sieve, or returns an error if the budget does not allow it.
+    pub fn add_sieve(&mut self) -> Result<(), Error> {
+        if self.budget.max_sieves.is_some_and(|max| self.sieves >= max) || self.deadline_passed() {
+            return Err(self.exhausted());
+        }
+        self.sieves += 1;
+        Ok(())
+    }
+
+    /// Registers the testing of a new candidate, or returns an error if the budget does not allow it.
+    pub fn add_candidate(&mut self) -> Result<(), Error> {
+        if self.budget.max_candidates.is_some_and(|max| self.candidates >= max) || self.deadline_passed() {
+            return Err(self.exhausted());
+        }
+        self.candidates += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    use super::{Budget, BudgetTracker};
+    use crate::Error;
+
+    #[test]
+    fn tracker() {
+        let mut tracker = BudgetTracker::new(Budget::unlimited().with_max_candidates(2).with_max_sieves(1));
+        tracker.add_sieve().unwrap();
+        tracker.add_candidate().unwrap();
+        tracker.add_candidate().unwrap();
+        let err = Error::AttemptsExhausted { attempts: 2 };
+        assert_eq!(tracker.add_candidate(), Err(err));
+        assert_eq!(tracker.add_sieve(), Err(err));
+
+        let expired = AtomicBool::new(false);
+        let deadline = || expired.load(Ordering::Relaxed);
+        let mut tracker = BudgetTracker::new(Budget::default().with_deadline(&deadline));
+        tracker.add_sieve().unwrap();
+        tracker.add_candidate().unwrap();
+        expired.store(true, Ordering::Relaxed);
+        let err = Error::AttemptsExhausted { attempts: 1 };
+        assert_eq!(tracker.add_candidate(), Err(err));
+        assert_eq!(tracker.add_sieve(), Err(err));
+    }
+
+    #[test]
+    fn debug() {
+        let deadline = || false;
+        assert_eq!(
+            format!("{:?}", Budget::unlimited().with_max_sieves(3).with_deadline(&deadline)),
+            "Budget { max_candidates: None, max_sieves: Some(3), deadline: Some(\"<closure>\") }"
+        );
+    }
+}
@@ -0,0 +1,395 @@
+//! Provable prime generation with Maurer's algorithm[^Maurer],
+//! producing primes along with certificates that can be verified without relying on probabilistic tests.
+//!
+//! [^Maurer]: U. M. Maurer, "Fast generation of prime numbers and secure public-key cryptographic parameters",
+//!   Journal of Cryptology 8, 123-155 (1995),
+//!   DOI: [10.1007/BF00202269](https://doi.org/10.1007/BF00202269)
+
+use alloc::vec::Vec;
+use core::num::NonZero;
+
+use crypto_bigint::{
+    Monty, NonZero as CTNonZero, Odd, PowBoundedExp, RandomBits, RandomMod, Unsigned, modular::Retrieve,
+};
+use rand_core::CryptoRng;
+
+use crate::{
+    error::Error,
+    hazmat::{
+        SMALL_PRIMES, SetBits, gcd_big_vartime, has_small_factor, random_odd_integer, to_working_precision,
+        two_powf_upper_bound,
+    },
+    presets::Flavor,
+};
+
+/// The maximum bit length of the primes that are generated (and verified) by trial division.
+const SMALL_BIT_LENGTH: u32 = 20;
+
+/// A step of a Pocklington certificate chain.
+///
+/// Certifies that `prime` is a prime, given that `factor` is a prime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PocklingtonStep<T> {
+    /// The certified prime `n = 2Rq + 1`.
+    pub prime: T,
+    /// The prime factor `q` of `n - 1`, such that `q > R`.
+    pub factor: T,
+    /// The witness `a` such that `a^(n-1) = 1 mod n` and `gcd(a^(2R) - 1, n) = 1`.
+    pub witness: T,
+}
+
+/// A Pocklington certificate chain for a prime, produced by [`random_provable_prime`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate<T> {
+    /// The smallest prime of the chain, which is small enough to be verified by trial division.
+    pub small_prime: T,
+    /// The certificate steps, each certifying a prime using the prime of the previous step
+    /// (or `small_prime` for the first step) as the factor.
+    pub steps: Vec<PocklingtonStep<T>>,
+}
+
+/// A prime generated by [`random_provable_prime`] along with its certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvablePrime<T> {
+    /// The prime.
+    pub prime: T,
+    /// The certificate of primality.
+    pub certificate: Certificate<T>,
+}
+
+/// Returns a random provable prime of size `bit_length` generated with Maurer's algorithm[^Maurer],
+/// along with a Pocklington certificate chain for it (see [`verify_certificate`]).
+///
+/// The algorithm recursively generates a prime `q` of a random relative size (at least a half of `bit_length`),
+/// and then searches for a prime `n = 2Rq + 1` with a random `R`, which can be proven prime
+/// with Pocklington's criterion. The primes of up to 20 bits are found and proven prime by trial division.
+///
+/// The resulting primes are close to uniformly distributed, and no probabilistic tests are involved.
+///
+/// Returns an error if `bit_length < 2`, or if `bit_length` is larger than the precision of `T`
+/// (for fixed-size integers).
+///
+/// [^Maurer]: U. M. Maurer, "Fast generation of prime numbers and secure public-key cryptographic parameters",
+///   Journal of Cryptology 8, 123-155 (1995),
+///   DOI: [10.1007/BF00202269](https://doi.org/10.1007/BF00202269)
+pub fn random_provable_prime<T, R>(rng: &mut R, bit_length: u32) -> Result<ProvablePrime<T>, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    if bit_length < 2 {
+        return Err(Error::BitLengthTooSmall {
+            bit_length,
+            flavor: Flavor::Any,
+        });
+    }
+
+    // Find the sizes of the primes in the chain, from the largest one down.
+    let mut bit_lengths = Vec::new();
+    let mut current = bit_length;
+    while current > SMALL_BIT_LENGTH {
+        bit_lengths.push(current);
+        current = factor_bit_length(rng, current);
+    }
+
+    let small_prime = random_small_prime(rng, current, bit_length)?;
+    let mut steps = Vec::<PocklingtonStep<T>>::with_capacity(bit_lengths.len());
+    for bit_length in bit_lengths.into_iter().rev() {
+        let factor = steps.last().map_or(&small_prime, |step| &step.prime);
+        let step = pocklington_step(rng, bit_length, factor);
+        steps.push(step);
+    }
+
+    let prime = steps.last().map_or(&small_prime, |step| &step.prime).clone();
+    Ok(ProvablePrime {
+        prime,
+        certificate: Certificate { small_prime, steps },
+    })
+}
+
+/// Checks the Pocklington certificate chain for `prime` (as produced by [`random_provable_prime`]).
+///
+/// The small prime is checked by trial division, and every step is checked with Pocklington's criterion:
+/// for `n = 2Rq + 1` where `q` is a prime and `q > R`, if there exists `a` such that `a^(n-1) = 1 mod n`
+/// and `gcd(a^(2R) - 1, n) = 1`, then `n` is prime[^HAC].
+///
+/// Returns `true` if the certificate is valid and proves that `prime` is prime.
+///
+/// [^HAC]: A. J. Menezes, P. C. van Oorschot, S. A. Vanstone, "Handbook of Applied Cryptography",
+///   CRC Press (1996), Fact 4.59, <https://cacr.uwaterloo.ca/hac/about/chap4.pdf>
+pub fn verify_certificate<T>(prime: &T, certificate: &Certificate<T>) -> bool
+where
+    T: Unsigned,
+{
+    // Bring everything to the same precision (only matters for boxed integers).
+    let zero = certificate
+        .steps
+        .iter()
+        .flat_map(|step| [&step.prime, &step.factor, &step.witness])
+        .chain([prime, &certificate.small_prime])
+        .fold(T::zero_like(prime), |zero, x| zero.wrapping_add(&T::zero_like(x)));
+
+    let small_prime = zero.wrapping_add(&certificate.small_prime);
+    if small_prime.bits_vartime() > SMALL_BIT_LENGTH || !is_small_prime(small_prime.as_ref()[0].0 as u32) {
+        return false;
+    }
+
+    let mut previous = small_prime;
+    for step in certificate.steps.iter() {
+        let n = zero.wrapping_add(&step.prime);
+        let factor = zero.wrapping_add(&step.factor);
+        let witness = zero.wrapping_add(&step.witness);
+        if factor != previous || !check_pocklington_step(&n, &factor, &witness) {
+            return false;
+        }
+        previous = n;
+    }
+
+    previous == zero.wrapping_add(prime)
+}
+
+/// Returns the size of the prime factor `q` used to generate a prime of size `bit_length`.
+fn factor_bit_length<R>(rng: &mut R, bit_length: u32) -> u32
+where
+    R: CryptoRng + ?Sized,
+{
+    // Only the primes significantly larger than the threshold get a random relative size;
+    // otherwise `q` must be at least a half of `n`.
+    let relative_size = if bit_length > 2 * SMALL_BIT_LENGTH {
+        loop {
+            // `r = 2^(s - 1)` for `s` uniformly distributed in `[0, 1)`,
+            // which is an approximation of the distribution of the relative size of the largest prime factor
+            // of a random integer.
+            // Note that we are using `two_powf_upper_bound()` which means `r >= 1/2`,
+            // as required for the Pocklington criterion.
+            let s = f64::from(rng.next_u32()) / (f64::from(u32::MAX) + 1.);
+            let relative_size = two_powf_upper_bound(s - 1.);
+            if f64::from(bit_length) * (1. - relative_size) > f64::from(SMALL_BIT_LENGTH) {
+                break relative_size;
+            }
+        }
+    } else {
+        0.5
+    };
+
+    (f64::from(bit_length) * relative_size) as u32 + 1
+}
+
+/// Returns a random prime of size `bit_length`, found by trial division,
+/// with the precision sufficient to hold a number of `precision_bits` bits.
+fn random_small_prime<T, R>(rng: &mut R, bit_length: u32, precision_bits: u32) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits,
+    R: CryptoRng + ?Sized,
+{
+    let bit_length = NonZero::new(bit_length).expect("`bit_length` is non-zero by construction");
+    loop {
+        let candidate = random_odd_integer::<T, _>(rng, bit_length, SetBits::Msb)?.get();
+        if is_small_prime(candidate.as_ref()[0].0 as u32) {
+            return to_working_precision(&candidate, precision_bits);
+        }
+    }
+}
+
+/// Checks if `num` is prime by trial division.
+///
+/// Assumes `num < 2^SMALL_BIT_LENGTH`.
+fn is_small_prime(num: u32) -> bool {
+    if num < 2 || num % 2 == 0 {
+        return num == 2;
+    }
+    SMALL_PRIMES
+        .iter()
+        .map(|prime| u32::from(*prime))
+        .take_while(|prime| prime * prime <= num)
+        .all(|prime| num % prime != 0)
+}
+
+/// Returns a prime `n = 2Rq + 1` of size `bit_length` (where `q` is the given prime factor)
+/// along with the witness of its primality.
+///
+/// Assumes that `q > 2^((bit_length - 1) / 2)` and `2q < 2^(bit_length - 1)`,
+/// and that `q` has the precision sufficient to hold `n`.
+fn pocklington_step<T, R>(rng: &mut R, bit_length: u32, factor: &T) -> PocklingtonStep<T>
+where
+    T: Unsigned + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let one = T::one_like(factor);
+    let two = one.wrapping_add(&one);
+    let two_q = factor.wrapping_shl_vartime(1);
+
+    // `R` is sampled from `[I + 1, 2I]`, where `I = floor(2^(bit_length - 1) / 2q)`,
+    // so that `n` has exactly `bit_length` bits.
+    let i = one.wrapping_shl_vartime(bit_length - 1) / CTNonZero::new(two_q.clone()).expect("`q` is non-zero");
+    let i_nz = CTNonZero::new(i.clone()).expect("`I` is non-zero since `2q < 2^(bit_length - 1)`");
+
+    loop {
+        let r = i.wrapping_add(&T::random_mod(rng, &i_nz)).wrapping_add(&one);
+        let n = r.wrapping_mul(&two_q).wrapping_add(&one);
+        if has_small_factor(&n) {
+            continue;
+        }
+
+        // A random witness from `[2, n - 2]`.
+        let n_minus_three = CTNonZero::new(n.wrapping_sub(&two).wrapping_sub(&one)).expect("`n` is greater than 3");
+        let witness = T::random_mod(rng, &n_minus_three).wrapping_add(&two);
+
+        if check_pocklington_step(&n, factor, &witness) {
+            return PocklingtonStep {
+                prime: n,
+                factor: factor.clone(),
+                witness,
+            };
+        }
+    }
+}
+
+/// Checks the Pocklington criterion for `n = 2Rq + 1`, assuming `q` is prime.
+///
+/// Assumes that all the arguments have the same precision.
+fn check_pocklington_step<T>(n: &T, factor: &T, witness: &T) -> bool
+where
+    T: Unsigned,
+{
+    let one = T::one_like(n);
+    let two = one.wrapping_add(&one);
+
+    let n_odd = match Odd::new(n.clone()).into_option() {
+        Some(n) => n,
+        None => return false,
+    };
+    if *n <= two.wrapping_add(&two) || witness < &two || witness >= &n.wrapping_sub(&one) {
+        return false;
+    }
+
+    // `n - 1 = 2Rq` with `q > R`.
+    let two_q = match CTNonZero::new(factor.wrapping_shl_vartime(1)).into_option() {
+        Some(two_q) => two_q,
+        None => return false,
+    };
+    let n_minus_one = n.wrapping_sub(&one);
+    let r = n_minus_one.clone() / &two_q;
+    if !bool::from((n_minus_one.clone() % &two_q).is_zero()) || r >= *factor {
+        return false;
+    }
+
+    let params = <T as Unsigned>::Monty::new_params_vartime(n_odd);
+    let witness = <T as Unsigned>::Monty::new(witness.clone(), params);
+
+    // `a^(n-1) = 1 mod n`
+    if witness
+        .pow_bounded_exp(&n_minus_one, n_minus_one.bits_vartime())
+        .retrieve()
+        != one
+    {
+        return false;
+    }
+
+    // `gcd(a^(2R) - 1, n) = 1`
+    let two_r = r.wrapping_shl_vartime(1);
+    let b = witness.pow_bounded_exp(&two_r, two_r.bits_vartime()).retrieve();
+    gcd_big_vartime(&b.wrapping_sub(&one), n) == one
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use crypto_bigint::{BoxedUint, RandomMod, U64, U128, U512, Unsigned};
+
+    use super::{Certificate, PocklingtonStep, ProvablePrime, random_provable_prime, verify_certificate};
+    use crate::{Error, Flavor, is_prime};
+
+    fn check_provable_prime<T: Unsigned + RandomMod>(provable_prime: &ProvablePrime<T>, bit_length: u32) {
+        let ProvablePrime { prime, certificate } = provable_prime;
+        assert_eq!(prime.bits_vartime(), bit_length);
+        assert!(is_prime(Flavor::Any, prime));
+        assert!(verify_certificate(prime, certificate));
+        for step in certificate.steps.iter() {
+            assert!(is_prime(Flavor::Any, &step.prime));
+        }
+    }
+
+    #[test]
+    fn provable_primes() {
+        let mut rng = rand::rng();
+        for bit_length in [2, 3, 10, 20, 21, 30, 40, 41, 64] {
+            let p = random_provable_prime::<U64, _>(&mut rng, bit_length).unwrap();
+            check_provable_prime(&p, bit_length);
+        }
+
+        let p = random_provable_prime::<U128, _>(&mut rng, 128).unwrap();
+        check_provable_prime(&p, 128);
+
+        let p = random_provable_prime::<U512, _>(&mut rng, 512).unwrap();
+        check_provable_prime(&p, 512);
+        assert!(p.certificate.steps.len() > 1);
+    }
+
+    #[test]
+    fn provable_primes_boxed() {
+        let mut rng = rand::rng();
+        let p = random_provable_prime::<BoxedUint, _>(&mut rng, 256).unwrap();
+        check_provable_prime(&p, 256);
+    }
+
+    #[test]
+    fn invalid_certificates() {
+        let mut rng = rand::rng();
+        let ProvablePrime { prime, certificate } = random_provable_prime::<U128, _>(&mut rng, 128).unwrap();
+
+        // Wrong prime
+        assert!(!verify_certificate(
+            &prime.wrapping_add(&U128::from(2u32)),
+            &certificate
+        ));
+
+        // Composite small prime
+        let mut bad = certificate.clone();
+        bad.small_prime = U128::from(15u32);
+        assert!(!verify_certificate(&prime, &bad));
+
+        // Broken chain
+        let mut bad = certificate.clone();
+        bad.steps.remove(0);
+        assert!(!verify_certificate(&prime, &bad));
+
+        // Wrong witness
+        let mut bad = certificate.clone();
+        let last = bad.steps.len() - 1;
+        bad.steps[last].witness = U128::ONE;
+        assert!(!verify_certificate(&prime, &bad));
+
+        // The factor is too small: `31 = 2 * 5 * 3 + 1` is prime and passes the witness checks,
+        // but `q > R` does not hold, so the criterion does not apply.
+        let bad = Certificate {
+            small_prime: U128::from(3u32),
+            steps: vec![PocklingtonStep {
+                prime: U128::from(31u32),
+                factor: U128::from(3u32),
+                witness: U128::from(3u32),
+            }],
+        };
+        assert!(!verify_certificate(&U128::from(31u32), &bad));
+    }
+
+    #[test]
+    fn errors() {
+        let mut rng = rand::rng();
+        assert_eq!(
+            random_provable_prime::<U64, _>(&mut rng, 1).unwrap_err(),
+            Error::BitLengthTooSmall {
+                bit_length: 1,
+                flavor: Flavor::Any
+            }
+        );
+        assert_eq!(
+            random_provable_prime::<U64, _>(&mut rng, 65).unwrap_err(),
+            Error::BitLengthTooLarge {
+                bit_length: 65,
+                bits_precision: 64
+            }
+        );
+    }
+}
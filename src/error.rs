@@ -4,6 +4,7 @@ use crate::Flavor;
 
 /// Errors returned by the crate's API.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Error {
     /// The requested bit length of the candidate is larger than the maximum size of the target integer type.
     BitLengthTooLarge {
@@ -19,6 +20,29 @@ pub enum Error {
         /// The requested flavor.
         flavor: Flavor,
     },
+    /// A parameter is outside of the range allowed by the algorithm.
+    InvalidParameter {
+        /// The name of the parameter.
+        name: &'static str,
+    },
+    /// The algorithm did not produce a result within the prescribed number of attempts,
+    /// or within the limits of a [`Budget`](`crate::Budget`).
+    AttemptsExhausted {
+        /// The number of attempts made.
+        attempts: usize,
+    },
+    /// The requested range does not contain primes of the chosen [`Flavor`](`crate::Flavor`).
+    NoPrimesInRange {
+        /// The requested flavor.
+        flavor: Flavor,
+    },
+    /// A base for the Lucas test could not be found within the prescribed number of attempts.
+    BaseNotFound {
+        /// The number of attempts made.
+        attempts: usize,
+    },
+    /// The thread pool for a parallel search could not be created.
+    ThreadPool,
 }
 
 impl fmt::Display for Error {
@@ -43,6 +67,29 @@ impl fmt::Display for Error {
                 ],
                 bit_length, flavor
             ),
+            Error::InvalidParameter { name } => write!(
+                f,
+                "The parameter `{}` is outside of the range allowed by the algorithm.",
+                name
+            ),
+            Error::AttemptsExhausted { attempts } => {
+                write!(f, "The algorithm did not produce a result after {} attempts.", attempts)
+            }
+            Error::NoPrimesInRange { flavor } => {
+                write!(
+                    f,
+                    "The requested range does not contain primes of the flavor {:?}.",
+                    flavor
+                )
+            }
+            Error::BaseNotFound { attempts } => {
+                write!(
+                    f,
+                    "Could not find a base for the Lucas test after {} attempts.",
+                    attempts
+                )
+            }
+            Error::ThreadPool => write!(f, "Could not create a thread pool."),
         }
     }
 }
@@ -2,11 +2,20 @@
 //!
 //! [^FIPS]: FIPS-186.5 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf>
 
+mod ffc;
+mod rsa;
+mod seed;
+mod shawe_taylor;
+
+pub use ffc::{FfcParameters, generate_ffc_parameters, validate_ffc_parameters};
+pub use rsa::{RsaPrimeWithConditions, generate_rsa_primes, generate_rsa_primes_with_conditions};
+pub use shawe_taylor::{ShaweTaylorPrime, shawe_taylor_random_prime};
+
 use crypto_bigint::{Odd, RandomMod, Unsigned};
 use rand_core::CryptoRng;
 
 use crate::{
-    hazmat::{LucasCheck, MillerRabin, Primality, SelfridgeBase, equals_primitive, lucas_test},
+    hazmat::{LucasCheck, MillerRabin, Primality, SelfridgeBase, equals_primitive, try_lucas_test},
     presets::Flavor,
 };
 
@@ -17,7 +26,7 @@ use crate::{
 /// - `mr_iterations` of Miller-Rabin check with random bases;
 /// - Regular Lucas check with Selfridge base (see [`SelfridgeBase`] for details), if `add_lucas_test` is `true`.
 ///
-/// See [`MillerRabin`] and [`lucas_test`] for more details about the checks;
+/// See [`MillerRabin`] and [`lucas_test`](`crate::hazmat::lucas_test`) for more details about the checks;
 /// use [`minimum_mr_iterations`](`crate::hazmat::minimum_mr_iterations`)
 /// to calculate the number of required iterations.
 ///
@@ -35,6 +44,10 @@ where
     match flavor {
         Flavor::Any => {}
         Flavor::Safe => return is_safe_prime(rng, candidate, mr_iterations, add_lucas_test),
+        Flavor::Blum => {
+            return candidate.as_ref()[0].0 & 3 == 3
+                && is_prime(rng, Flavor::Any, candidate, mr_iterations, add_lucas_test);
+        }
     }
 
     if equals_primitive(candidate, 1) {
@@ -61,9 +74,10 @@ where
     }
 
     if add_lucas_test {
-        match lucas_test(odd_candidate, SelfridgeBase, LucasCheck::Strong) {
-            Primality::Composite => return false,
-            Primality::Prime => return true,
+        // Failing to find a Lucas base is not known to happen; if it does, reject the candidate.
+        match try_lucas_test(odd_candidate, SelfridgeBase, LucasCheck::Strong) {
+            Ok(Primality::Composite) | Err(_) => return false,
+            Ok(Primality::Prime) => return true,
             _ => {}
         }
     }
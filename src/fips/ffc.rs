@@ -0,0 +1,477 @@
+//! Finite field cryptography (DSA and Diffie-Hellman) domain parameter generation.
+
+use alloc::{vec, vec::Vec};
+
+use crypto_bigint::{
+    Monty, NonZero as CTNonZero, Odd, PowBoundedExp, RandomBits, RandomMod, Unsigned, modular::Retrieve,
+};
+use rand_core::CryptoRng;
+
+use super::{
+    is_prime,
+    seed::{block_bits, hash_blocks, increment_seed, integer_from_blocks},
+};
+use crate::{error::Error, hazmat::zero_with_bit_length, presets::Flavor};
+
+/// The string used in the generation of the canonical generator, FIPS-186.4[^FIPS], Appendix A.2.3.
+///
+/// [^FIPS]: FIPS-186.4 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-4.pdf>
+const GGEN: &[u8] = b"ggen";
+
+/// Finite field domain parameters generated by [`generate_ffc_parameters`].
+///
+/// All the fields are needed to validate the parameters with [`validate_ffc_parameters`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FfcParameters<T> {
+    /// The field prime.
+    pub p: T,
+    /// The prime order of the subgroup, dividing `p - 1`.
+    pub q: T,
+    /// The generator of the subgroup of order `q`.
+    pub g: T,
+    /// The seed `p` and `q` were derived from.
+    pub domain_parameter_seed: Vec<u8>,
+    /// The number of candidates for `p` tested before the prime was found.
+    pub counter: u32,
+    /// The index used in the generation of `g`.
+    pub index: u8,
+}
+
+/// Returns the number of Miller-Rabin iterations for `p` and `q` respectively,
+/// as given in FIPS-186.4[^FIPS], Table C.1, or `None` if `(p_bits, q_bits)` is not an allowed pair.
+///
+/// [^FIPS]: FIPS-186.4 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-4.pdf>
+const fn mr_iterations(p_bits: u32, q_bits: u32) -> Option<(usize, usize)> {
+    match (p_bits, q_bits) {
+        (1024, 160) => Some((40, 40)),
+        (2048, 224) => Some((56, 56)),
+        (2048, 256) => Some((56, 64)),
+        (3072, 256) => Some((64, 64)),
+        _ => None,
+    }
+}
+
+/// Generates domain parameters for finite field cryptography (DSA or Diffie-Hellman):
+/// a prime `q` of size `q_bits`, a prime `p` of size `p_bits` such that `p = 1 mod 2q`,
+/// and a generator `g` of the subgroup of order `q`.
+///
+/// `p` and `q` are generated from a random seed with the procedure
+/// from FIPS-186.4[^FIPS], Appendix A.1.1.2, and `g` is generated with the verifiable canonical procedure
+/// from Appendix A.2.3 with the given `index`.
+/// The seed is `q_bits` bits long.
+/// `hash` is the approved hash function used in the procedure (e.g. SHA-256); its output must be at least
+/// `q_bits` bits long, and it is interpreted as a big-endian integer.
+///
+/// The allowed `(p_bits, q_bits)` pairs are `(1024, 160)`, `(2048, 224)`, `(2048, 256)`, and `(3072, 256)`.
+/// The number of Miller-Rabin iterations is taken from Table C.1 of the standard.
+///
+/// Returns an error if the sizes are not an allowed pair, if the hash output is too short,
+/// or if `p_bits` is larger than the precision of `T` (for fixed-size integers).
+///
+/// [^FIPS]: FIPS-186.4 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-4.pdf>
+pub fn generate_ffc_parameters<T, R, H>(
+    rng: &mut R,
+    p_bits: u32,
+    q_bits: u32,
+    index: u8,
+    hash: impl Fn(&[u8]) -> H,
+) -> Result<FfcParameters<T>, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+    H: AsRef<[u8]>,
+{
+    let (p_mr_iterations, q_mr_iterations) = check_parameters::<T, H>(p_bits, q_bits, &hash)?;
+
+    let mut domain_parameter_seed = vec![0u8; q_bits as usize / 8];
+    loop {
+        // Step 5
+        rng.fill_bytes(&mut domain_parameter_seed);
+
+        // Steps 6-8
+        let q = q_from_seed::<T, H>(&domain_parameter_seed, p_bits, q_bits, &hash)?;
+        if !is_prime(rng, Flavor::Any, &q, q_mr_iterations, false) {
+            continue;
+        }
+
+        // Steps 9-10
+        if let Some((p, counter)) = p_from_seed(rng, &domain_parameter_seed, &q, p_bits, p_mr_iterations, &hash, None) {
+            let g = canonical_generator(&p, &q, &domain_parameter_seed, index, &hash)?;
+            return Ok(FfcParameters {
+                p,
+                q,
+                g,
+                domain_parameter_seed,
+                counter,
+                index,
+            });
+        }
+    }
+}
+
+/// Validates the domain parameters produced by [`generate_ffc_parameters`] with the given `hash`,
+/// according to the procedures from FIPS-186.4[^FIPS], Appendices A.1.1.3 and A.2.4.
+///
+/// Returns `true` if `p` and `q` are probable primes derived from the seed with the recorded counter,
+/// and `g` is the canonical generator for the recorded index.
+///
+/// [^FIPS]: FIPS-186.4 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-4.pdf>
+pub fn validate_ffc_parameters<T, R, H>(rng: &mut R, parameters: &FfcParameters<T>, hash: impl Fn(&[u8]) -> H) -> bool
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+    H: AsRef<[u8]>,
+{
+    let FfcParameters {
+        p,
+        q,
+        g,
+        domain_parameter_seed,
+        counter,
+        index,
+    } = parameters;
+
+    let p_bits = p.bits_vartime();
+    let q_bits = q.bits_vartime();
+    let Ok((p_mr_iterations, q_mr_iterations)) = check_parameters::<T, H>(p_bits, q_bits, &hash) else {
+        return false;
+    };
+    if domain_parameter_seed.len() * 8 < q_bits as usize || *counter >= 4 * p_bits {
+        return false;
+    }
+
+    let Ok(computed_q) = q_from_seed::<T, H>(domain_parameter_seed, p_bits, q_bits, &hash) else {
+        return false;
+    };
+    if &computed_q != q || !is_prime(rng, Flavor::Any, q, q_mr_iterations, false) {
+        return false;
+    }
+
+    match p_from_seed(
+        rng,
+        domain_parameter_seed,
+        q,
+        p_bits,
+        p_mr_iterations,
+        &hash,
+        Some(*counter),
+    ) {
+        Some((computed_p, computed_counter)) if &computed_p == p && computed_counter == *counter => {}
+        _ => return false,
+    }
+
+    canonical_generator(p, q, domain_parameter_seed, *index, &hash).is_ok_and(|computed_g| &computed_g == g)
+}
+
+/// Checks the requested sizes and the hash output length,
+/// and returns the number of Miller-Rabin iterations for `p` and `q`.
+fn check_parameters<T, H>(p_bits: u32, q_bits: u32, hash: &impl Fn(&[u8]) -> H) -> Result<(usize, usize), Error>
+where
+    T: Unsigned + RandomBits,
+    H: AsRef<[u8]>,
+{
+    if !matches!(p_bits, 1024 | 2048 | 3072) {
+        return Err(Error::InvalidParameter { name: "p_bits" });
+    }
+    let iterations = mr_iterations(p_bits, q_bits).ok_or(Error::InvalidParameter { name: "q_bits" })?;
+    if hash(&[]).as_ref().len() * 8 < q_bits as usize {
+        return Err(Error::InvalidParameter { name: "hash" });
+    }
+    // Check that the result will fit in `T`.
+    zero_with_bit_length::<T>(p_bits)?;
+    Ok(iterations)
+}
+
+/// Derives a candidate for `q` from the seed (FIPS-186.4, Appendix A.1.1.2, steps 6-7).
+fn q_from_seed<T, H>(seed: &[u8], p_bits: u32, q_bits: u32, hash: &impl Fn(&[u8]) -> H) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits,
+    H: AsRef<[u8]>,
+{
+    let zero = zero_with_bit_length::<T>(p_bits)?;
+    let digest = hash(seed);
+    let outlen = digest.as_ref().len() as u32 * 8;
+    let mut q = integer_from_blocks(&zero, &[digest], outlen, q_bits);
+    q.set_bit_vartime(0, true);
+    Ok(q)
+}
+
+/// Searches for `p` derived from the seed (FIPS-186.4, Appendix A.1.1.2, steps 9-10).
+///
+/// If `max_counter` is given, stops after checking the candidate with that counter.
+/// Returns the prime and the counter, or `None` if no prime was found.
+fn p_from_seed<T, R, H>(
+    rng: &mut R,
+    seed: &[u8],
+    q: &T,
+    p_bits: u32,
+    mr_iterations: usize,
+    hash: &impl Fn(&[u8]) -> H,
+    max_counter: Option<u32>,
+) -> Option<(T, u32)>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+    H: AsRef<[u8]>,
+{
+    let zero = zero_with_bit_length::<T>(p_bits).ok()?;
+    let one = T::one_like(&zero);
+    let outlen = hash(seed).as_ref().len() as u32 * 8;
+    let n = p_bits.div_ceil(outlen) - 1;
+
+    let two_q = CTNonZero::new(zero.wrapping_add(q).wrapping_shl_vartime(1)).into_option()?;
+
+    // Step 9: offset = 1
+    let mut current_seed = Vec::from(seed);
+    increment_seed(&mut current_seed, 1);
+
+    let max_counter = max_counter.unwrap_or(4 * p_bits - 1);
+    for counter in 0..=max_counter {
+        // Steps 10.1-10.3: X = W + 2^(L-1); this also advances the offset (step 10.9).
+        let x = integer_from_blocks(&zero, &hash_blocks(&mut current_seed, n, hash), outlen, p_bits);
+
+        // Steps 10.4-10.5: p = X - (X mod 2q - 1)
+        let c = x.clone() % &two_q;
+        let p = x.wrapping_sub(&c).wrapping_add(&one);
+
+        // Steps 10.6-10.8
+        if p.bits_vartime() == p_bits && is_prime(rng, Flavor::Any, &p, mr_iterations, false) {
+            return Some((p, counter));
+        }
+    }
+
+    None
+}
+
+/// Generates the canonical generator of the subgroup of order `q`, according to FIPS-186.4[^FIPS], Appendix A.2.3.
+///
+/// [^FIPS]: FIPS-186.4 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-4.pdf>
+fn canonical_generator<T, H>(p: &T, q: &T, seed: &[u8], index: u8, hash: &impl Fn(&[u8]) -> H) -> Result<T, Error>
+where
+    T: Unsigned,
+    H: AsRef<[u8]>,
+{
+    // Step 3: e = (p - 1) / q
+    let q = CTNonZero::new(q.clone())
+        .into_option()
+        .ok_or(Error::InvalidParameter { name: "q" })?;
+    let e = p.wrapping_sub(&T::one_like(p)) / &q;
+
+    let p = Odd::new(p.clone())
+        .into_option()
+        .ok_or(Error::InvalidParameter { name: "p" })?;
+    let params = <T as Unsigned>::Monty::new_params_vartime(p.clone());
+    let two = T::from_limb_like(2u32.into(), &p);
+
+    for count in 1..=u16::MAX {
+        // Steps 7-8: W = hash(domain_parameter_seed || "ggen" || index || count)
+        let mut data = Vec::with_capacity(seed.len() + GGEN.len() + 3);
+        data.extend_from_slice(seed);
+        data.extend_from_slice(GGEN);
+        data.push(index);
+        data.extend_from_slice(&count.to_be_bytes());
+        let digest = hash(&data);
+
+        let mut w = T::zero_like(&p);
+        for (position, bit) in block_bits(digest.as_ref()) {
+            if bit {
+                w.set_bit_vartime(position, true);
+            }
+        }
+
+        // Steps 9-10: g = W^e mod p, retrying if g < 2
+        let w = <T as Unsigned>::Monty::new(w % p.as_nz_ref(), params.clone());
+        let g = w.pow_bounded_exp(&e, e.bits_vartime()).retrieve();
+        if g >= two {
+            return Ok(g);
+        }
+    }
+
+    Err(Error::AttemptsExhausted {
+        attempts: u16::MAX.into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto_bigint::{
+        BoxedUint, Monty, NonZero, Odd, PowBoundedExp, RandomBits, RandomMod, U1024, U2048, Unsigned, modular::Retrieve,
+    };
+    use sha2::{Digest, Sha256};
+
+    use super::{FfcParameters, generate_ffc_parameters, validate_ffc_parameters};
+    use crate::{Error, Flavor, is_prime};
+
+    fn sha256(data: &[u8]) -> [u8; 32] {
+        Sha256::digest(data).into()
+    }
+
+    fn check_parameters<T: Unsigned + RandomMod>(parameters: &FfcParameters<T>, p_bits: u32, q_bits: u32) {
+        let FfcParameters { p, q, g, .. } = parameters;
+        assert_eq!(p.bits_vartime(), p_bits);
+        assert_eq!(q.bits_vartime(), q_bits);
+        assert!(is_prime(Flavor::Any, p));
+        assert!(is_prime(Flavor::Any, q));
+
+        // p = 1 mod 2q
+        let two_q = NonZero::new(q.wrapping_shl_vartime(1)).unwrap();
+        assert_eq!(p.clone() % two_q, T::one_like(p));
+
+        // g has order q
+        let params = <T as Unsigned>::Monty::new_params_vartime(Odd::new(p.clone()).unwrap());
+        let g_monty = <T as Unsigned>::Monty::new(g.clone(), params);
+        assert!(g > &T::one_like(g));
+        assert_eq!(g_monty.pow_bounded_exp(q, q.bits_vartime()).retrieve(), T::one_like(p));
+    }
+
+    #[test]
+    fn parameters() {
+        let mut rng = rand::rng();
+        let parameters = generate_ffc_parameters::<U1024, _, _>(&mut rng, 1024, 160, 1, sha256).unwrap();
+        check_parameters(&parameters, 1024, 160);
+        assert!(validate_ffc_parameters(&mut rng, &parameters, sha256));
+    }
+
+    #[test]
+    fn parameters_boxed() {
+        let mut rng = rand::rng();
+        let parameters = generate_ffc_parameters::<BoxedUint, _, _>(&mut rng, 1024, 160, 1, sha256).unwrap();
+        check_parameters(&parameters, 1024, 160);
+        assert!(validate_ffc_parameters(&mut rng, &parameters, sha256));
+    }
+
+    #[test]
+    fn tampered_parameters() {
+        let mut rng = rand::rng();
+        let parameters = generate_ffc_parameters::<U1024, _, _>(&mut rng, 1024, 160, 1, sha256).unwrap();
+
+        let mut tampered = parameters.clone();
+        tampered.counter += 1;
+        assert!(!validate_ffc_parameters(&mut rng, &tampered, sha256));
+
+        let mut tampered = parameters.clone();
+        tampered.index = 2;
+        assert!(!validate_ffc_parameters(&mut rng, &tampered, sha256));
+
+        let mut tampered = parameters.clone();
+        tampered.domain_parameter_seed[0] ^= 1;
+        assert!(!validate_ffc_parameters(&mut rng, &tampered, sha256));
+
+        let mut tampered = parameters.clone();
+        tampered.g = tampered.g.wrapping_add(&U1024::ONE);
+        assert!(!validate_ffc_parameters(&mut rng, &tampered, sha256));
+    }
+
+    fn from_hex<T: Unsigned>(zero: &T, hex: &str) -> T {
+        let mut result = zero.clone();
+        for (i, digit) in hex.bytes().rev().enumerate() {
+            let digit = (digit as char).to_digit(16).unwrap();
+            for bit in 0..4 {
+                if digit & (1 << bit) != 0 {
+                    result.set_bit_vartime(i as u32 * 4 + bit, true);
+                }
+            }
+        }
+        result
+    }
+
+    fn known_answer<T: Unsigned + RandomBits + RandomMod>(
+        zero: &T,
+        (p, q, g): (&str, &str, &str),
+        seed: &str,
+        counter: u32,
+        index: u8,
+    ) {
+        let mut rng = rand::rng();
+        let parameters = FfcParameters {
+            p: from_hex(zero, p),
+            q: from_hex(zero, q),
+            g: from_hex(zero, g),
+            domain_parameter_seed: (0..seed.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&seed[i..i + 2], 16).unwrap())
+                .collect(),
+            counter,
+            index,
+        };
+        assert!(validate_ffc_parameters(&mut rng, &parameters, sha256));
+    }
+
+    #[test]
+    fn known_answers() {
+        // The vectors were produced by OpenSSL 3.0 (`EVP_PKEY_paramgen()` for DSA with `type = "fips186_4"`
+        // and `digest = "SHA256"`), an independent implementation of Appendices A.1.1.2 and A.2.3.
+        // The 1024-bit case also covers a hash output longer than `q` and a seed longer than `q`.
+        known_answer(
+            &U2048::ZERO,
+            (
+                concat![
+                    "a7012b6c3647b911c15ab85461d75e20fac08838dfd7830f4cc264a2df0c48e4efb9a28734183a60514a539600ca0402",
+                    "ff9d234d6244bd4f9e589865ec48bbd0e27f5f9663998b34781a1cb8a8fb7a1a33f11cb98030f163b26a4771fde4cacf",
+                    "3e5f6bb2f90f7d8e2247c2dc662120484cdbce216d8c052441691c066efe1bd2001024a0b1251bbf876ace9342ddcfe0",
+                    "1714a8d5b9ef1495494f3f17f72642ee1deaefcce716241ee4ebd03529bc5648eda7a44a783725fc52ac1eaccbc99d81",
+                    "c18af3c36b44ec3c1483f8fe79ec4c0a47b4a726291643d5b930fa22ca67d937af2e4612b14720352ebd249ada7845d4",
+                    "23368c445daae5a4543c54def2075155",
+                ],
+                "d992d6dd85551338c2410139f77e9b8831abd8b280aa6d8133d24e8a2f7abeff",
+                concat![
+                    "52a00c64991d37197e067ab21599ccde5d0f45713490cbeee5e2dc866f0e45eb9b1875506e06a0fd5fe390c74babec86",
+                    "f7fac7c5576a86f884e8b08f762e80c8d488fdb0854e71232f9a35611ac710299a96bd2127cb10827ae68437a29b02a8",
+                    "53ab1718d87984d4cb9d512f7afc96e6056b06642a00458b4006ee6c27e3362c4206693c20fa89d57bd3a3ac1957b598",
+                    "bcc63eaca6afc4d637b29dd6eb4c173c6ddc0181d962093f2dc04b26a54e2e2218aada2125fffe1f350e4f6037c6ace1",
+                    "47b840e412ec82ae63de395d8d645cdac1f1c5ef809b5a1711d5f99b77616d5bf6e4fccdf592ca2b281fbaf6f7e7e84b",
+                    "8cf715182a51a18e679da4cc5b5218d3",
+                ],
+            ),
+            "2a2bf03d1f798cbf6fa223ad10144d78882fb422622ab90e09f2605080ebf8fa",
+            873,
+            1,
+        );
+        known_answer(
+            &BoxedUint::zero_with_precision(1024),
+            (
+                concat![
+                    "9eb7868a60e999219ca3066a22f20536611615a229f5fa87622b79a0302bb69664dc7ff4ec11ce65022b8a589e371047",
+                    "d6526a21ac922e551e032dec50f8405032fc6c6aa1f01c6c9a764d6382a676abb455dda9751602bba648a0c9f47b1690",
+                    "e4d176917163166102c19b7b1eabb52993d02db413feb59a356e8629bbb905fd",
+                ],
+                "ebea54470d6a7c6df8560c8b273c75cc4e6f1f03",
+                concat![
+                    "6d7100e23b7672932bbe547465508fd15f381299da9295f2c126afae7ce26db1d4005e3222fa29176405290da9ae5010",
+                    "58e780b78f22e4352b8b424f936f76a69904b99763bc4477cb88ff605d38e78b041cacdf39ddf8fc15263dfa07c84ba7",
+                    "32bb858fecc5c4cd19568e87c2a1dab6774da870caab9cddc5ee4e85b7dc3046",
+                ],
+            ),
+            "26cd0bc125db123c2bd25943ee792efad5f4ef099f60247db2dbc09cf45a3fae",
+            176,
+            42,
+        );
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let mut rng = rand::rng();
+        assert_eq!(
+            generate_ffc_parameters::<U2048, _, _>(&mut rng, 1536, 160, 1, sha256).unwrap_err(),
+            Error::InvalidParameter { name: "p_bits" }
+        );
+        assert_eq!(
+            generate_ffc_parameters::<U2048, _, _>(&mut rng, 2048, 160, 1, sha256).unwrap_err(),
+            Error::InvalidParameter { name: "q_bits" }
+        );
+        assert_eq!(
+            generate_ffc_parameters::<U2048, _, _>(&mut rng, 2048, 256, 1, |data: &[u8]| {
+                <[u8; 20]>::try_from(&sha256(data)[..20]).unwrap()
+            })
+            .unwrap_err(),
+            Error::InvalidParameter { name: "hash" }
+        );
+        assert_eq!(
+            generate_ffc_parameters::<U1024, _, _>(&mut rng, 2048, 256, 1, sha256).unwrap_err(),
+            Error::BitLengthTooLarge {
+                bit_length: 2048,
+                bits_precision: 1024
+            }
+        );
+    }
+}
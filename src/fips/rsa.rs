@@ -0,0 +1,564 @@
+//! RSA prime generation as prescribed by FIPS-186.5, Appendix A.1.
+
+use core::num::NonZero;
+
+use crypto_bigint::{
+    Monty, NonZero as CTNonZero, Odd, PowBoundedExp, RandomBits, RandomMod, Unsigned, modular::Retrieve,
+};
+use rand_core::CryptoRng;
+
+use super::is_prime;
+use crate::{
+    budget::Budget,
+    error::Error,
+    generic::sieve_and_find_with_budget,
+    hazmat::{
+        SetBits, SmallFactorsSieveFactory, gcd_big_vartime, minimum_mr_iterations, random_odd_integer,
+        to_working_precision,
+    },
+    presets::Flavor,
+};
+
+/// The top 64 bits of `sqrt(2) * 2^63`, rounded down.
+const SQRT_2_TOP_BITS: u64 = 0xB504_F333_F9DE_6484;
+
+/// The minimum modulus size allowed by FIPS-186.5[^FIPS], Section 5.1.
+///
+/// [^FIPS]: FIPS-186.5 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf>
+const MIN_NLEN: u32 = 2048;
+
+/// Returns `log2` of the target probability of a composite passing the Miller-Rabin checks,
+/// as given in FIPS-186.5[^FIPS], Table B.1.
+///
+/// [^FIPS]: FIPS-186.5 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf>
+const fn mr_log2_target(nlen: u32) -> u32 {
+    if nlen >= 4096 {
+        144
+    } else if nlen >= 3072 {
+        128
+    } else {
+        112
+    }
+}
+
+/// Returns the minimum bit length of the auxiliary primes (exclusive),
+/// and the maximum sum of the bit lengths of the auxiliary primes for one prime factor (exclusive),
+/// as given in FIPS-186.5[^FIPS], Table A.1 (for probable primes with conditions).
+///
+/// [^FIPS]: FIPS-186.5 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf>
+const fn aux_prime_bounds(nlen: u32) -> (u32, u32) {
+    if nlen >= 4096 {
+        (200, 2030)
+    } else if nlen >= 3072 {
+        (170, 1518)
+    } else {
+        (140, 1007)
+    }
+}
+
+/// A prime factor of an RSA modulus generated by [`generate_rsa_primes_with_conditions`],
+/// along with the auxiliary primes used to construct it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsaPrimeWithConditions<T> {
+    /// The prime factor of the modulus.
+    pub prime: T,
+    /// The auxiliary prime dividing `prime - 1`.
+    pub aux1: T,
+    /// The auxiliary prime dividing `prime + 1`.
+    pub aux2: T,
+}
+
+/// Generates a pair of primes `(p, q)` for an RSA modulus of size `nlen`
+/// with the public exponent `e`, following FIPS-186.5[^FIPS], Appendix A.1.3.
+///
+/// Each of the returned primes is `nlen / 2` bits long and satisfies:
+/// - `p >= sqrt(2) * 2^(nlen/2 - 1)`, so that `p * q` is exactly `nlen` bits long;
+/// - `gcd(p - 1, e) == 1`;
+/// - `|p - q| > 2^(nlen/2 - 100)`.
+///
+/// The number of Miller-Rabin iterations is calculated with
+/// [`minimum_mr_iterations`](`crate::hazmat::minimum_mr_iterations`)
+/// for the error probabilities listed in Table B.1 of the standard.
+///
+/// Returns an error if `nlen` is odd or smaller than 2048, if `e` is not odd or outside of `(2^16, 2^256)`,
+/// or if `nlen / 2` is larger than the precision of `T` (for fixed-size integers).
+/// If no prime is found within `5 * nlen / 2` candidates (as mandated by the standard),
+/// [`Error::AttemptsExhausted`] is returned.
+///
+/// [^FIPS]: FIPS-186.5 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf>
+pub fn generate_rsa_primes<T, R>(rng: &mut R, nlen: u32, e: &T) -> Result<(T, T), Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    check_parameters(nlen, e)?;
+
+    let prime_bits = nlen / 2;
+    let mr_iterations =
+        minimum_mr_iterations(prime_bits, mr_log2_target(nlen)).ok_or(Error::InvalidParameter { name: "nlen" })?;
+    let max_attempts = 5 * prime_bits as usize;
+
+    let p = generate_prime(rng, prime_bits, e, mr_iterations, None, max_attempts)?;
+    let q = generate_prime(rng, prime_bits, e, mr_iterations, Some(&p), max_attempts)?;
+    Ok((p, q))
+}
+
+/// Generates a pair of primes `(p, q)` for an RSA modulus of size `nlen`
+/// with the public exponent `e`, such that `p - 1`, `p + 1`, `q - 1` and `q + 1`
+/// have large prime factors (the auxiliary primes) of size `aux_prime_bits`.
+///
+/// This follows the procedure for probable primes with conditions based on auxiliary probable primes
+/// of FIPS-186.5[^FIPS], Appendix A.1.5.
+/// The auxiliary primes are found with [`SmallFactorsSieveFactory`] and tested with [`is_prime`];
+/// the search for each prime factor starts from a random point in the residue class
+/// mandated by the auxiliary primes, which is constructed using the Chinese Remainder Theorem.
+///
+/// The returned primes satisfy the same conditions as the ones returned by [`generate_rsa_primes`].
+/// Additionally, `|Xp - Xq| > 2^(nlen/2 - 100)` for the starting points `Xp` and `Xq` of the search.
+///
+/// `aux_prime_bits` must be within the bounds listed in Table A.1 of the standard,
+/// e.g. for `nlen = 2048` it must be greater than 140 and less than 504
+/// (since the sum of the bit lengths of the two auxiliary primes must be less than 1007).
+///
+/// Returns an error if `nlen`, `e` or `aux_prime_bits` are outside of the allowed ranges,
+/// or if `nlen / 2` is larger than the precision of `T` (for fixed-size integers).
+/// If no prime is found within `5 * nlen / 2` candidates from a starting point
+/// (or, for the auxiliary primes, within `5 * nlen / 2` candidates that passed the sieve),
+/// [`Error::AttemptsExhausted`] is returned.
+///
+/// [^FIPS]: FIPS-186.5 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf>
+pub fn generate_rsa_primes_with_conditions<T, R>(
+    rng: &mut R,
+    nlen: u32,
+    e: &T,
+    aux_prime_bits: u32,
+) -> Result<(RsaPrimeWithConditions<T>, RsaPrimeWithConditions<T>), Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    check_parameters(nlen, e)?;
+
+    let (min_aux_bits, max_aux_bits_sum) = aux_prime_bounds(nlen);
+    if aux_prime_bits <= min_aux_bits || 2 * aux_prime_bits >= max_aux_bits_sum {
+        return Err(Error::InvalidParameter { name: "aux_prime_bits" });
+    }
+
+    let prime_bits = nlen / 2;
+    let log2_target = mr_log2_target(nlen);
+    let mr_iterations =
+        minimum_mr_iterations(prime_bits, log2_target).ok_or(Error::InvalidParameter { name: "nlen" })?;
+    let aux_mr_iterations =
+        minimum_mr_iterations(aux_prime_bits, log2_target).ok_or(Error::InvalidParameter { name: "aux_prime_bits" })?;
+    let max_attempts = 5 * prime_bits as usize;
+
+    let (p, xp) = generate_prime_with_conditions(
+        rng,
+        prime_bits,
+        e,
+        aux_prime_bits,
+        mr_iterations,
+        aux_mr_iterations,
+        max_attempts,
+    )?;
+
+    loop {
+        let (q, xq) = generate_prime_with_conditions(
+            rng,
+            prime_bits,
+            e,
+            aux_prime_bits,
+            mr_iterations,
+            aux_mr_iterations,
+            max_attempts,
+        )?;
+        if differ_enough(&p.prime, &q.prime, prime_bits - 100) && differ_enough(&xp, &xq, prime_bits - 100) {
+            return Ok((p, q));
+        }
+    }
+}
+
+/// Checks the RSA modulus size and the public exponent against the requirements of FIPS-186.5, Appendix A.1.
+fn check_parameters<T: Unsigned>(nlen: u32, e: &T) -> Result<(), Error> {
+    if nlen < MIN_NLEN || nlen % 2 != 0 {
+        return Err(Error::InvalidParameter { name: "nlen" });
+    }
+
+    let e_bits = e.bits_vartime();
+    if !bool::from(e.is_odd()) || e_bits <= 16 || e_bits > 256 {
+        return Err(Error::InvalidParameter { name: "e" });
+    }
+
+    Ok(())
+}
+
+/// Implements steps 4 (if `other` is `None`) and 5 (if `other` is `p`) of FIPS-186.5, Appendix A.1.3.
+fn generate_prime<T, R>(
+    rng: &mut R,
+    prime_bits: u32,
+    e: &T,
+    mr_iterations: usize,
+    other: Option<&T>,
+    max_attempts: usize,
+) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let bit_length = NonZero::new(prime_bits).expect("`prime_bits` is non-zero by construction");
+    let mut attempts = 0;
+    loop {
+        // Candidates with the MSB unset are rejected by the lower bound check anyway,
+        // so setting the MSB right away does not change the distribution of the result.
+        let candidate = random_odd_integer::<T, _>(rng, bit_length, SetBits::Msb)?.get();
+
+        if let Some(other) = other {
+            if !differ_enough(other, &candidate, prime_bits - 100) {
+                continue;
+            }
+        }
+
+        if !above_sqrt2_bound(&candidate, prime_bits) {
+            continue;
+        }
+
+        let candidate_minus_one = candidate.wrapping_sub(&T::one_like(&candidate));
+        if gcd_big_vartime(&candidate_minus_one, e) == T::one_like(&candidate)
+            && is_prime(rng, Flavor::Any, &candidate, mr_iterations, false)
+        {
+            return Ok(candidate);
+        }
+
+        attempts += 1;
+        if attempts >= max_attempts {
+            return Err(Error::AttemptsExhausted { attempts });
+        }
+    }
+}
+
+/// Generates the auxiliary primes and finds a prime factor based on them.
+///
+/// Returns the prime along with the starting point `X` of the search.
+fn generate_prime_with_conditions<T, R>(
+    rng: &mut R,
+    prime_bits: u32,
+    e: &T,
+    aux_prime_bits: u32,
+    mr_iterations: usize,
+    aux_mr_iterations: usize,
+    max_attempts: usize,
+) -> Result<(RsaPrimeWithConditions<T>, T), Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let aux1 = generate_aux_prime::<T, R>(rng, aux_prime_bits, aux_mr_iterations, max_attempts)?;
+    // The auxiliary primes must be coprime, which for two primes means they must be different.
+    let aux2 = loop {
+        let aux2 = generate_aux_prime::<T, R>(rng, aux_prime_bits, aux_mr_iterations, max_attempts)?;
+        if aux2 != aux1 {
+            break aux2;
+        }
+    };
+
+    let (prime, x) = prime_from_aux_primes(rng, prime_bits, e, &aux1, &aux2, mr_iterations, max_attempts)?;
+    Ok((RsaPrimeWithConditions { prime, aux1, aux2 }, x))
+}
+
+/// Generates a probable prime of exactly `bit_length` bits,
+/// testing at most `max_attempts` candidates that passed the sieve.
+fn generate_aux_prime<T, R>(rng: &mut R, bit_length: u32, mr_iterations: usize, max_attempts: usize) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let factory = SmallFactorsSieveFactory::<T>::new(Flavor::Any, bit_length, SetBits::Msb)?;
+    let prime = sieve_and_find_with_budget(
+        rng,
+        factory,
+        |rng, candidate| is_prime(rng, Flavor::Any, candidate, mr_iterations, false),
+        Budget::unlimited().with_max_candidates(max_attempts),
+    )?;
+    Ok(prime.expect("`SmallFactorsSieveFactory` never stops producing sieves"))
+}
+
+/// Finds a prime `p` of size `prime_bits` such that `r1 | p - 1` and `r2 | p + 1`,
+/// as prescribed by FIPS-186.5[^FIPS], Appendix B.9.
+///
+/// Returns the prime along with the starting point `X` of the search.
+///
+/// [^FIPS]: FIPS-186.5 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf>
+fn prime_from_aux_primes<T, R>(
+    rng: &mut R,
+    prime_bits: u32,
+    e: &T,
+    r1: &T,
+    r2: &T,
+    mr_iterations: usize,
+    max_attempts: usize,
+) -> Result<(T, T), Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let r1 = to_working_precision(r1, prime_bits)?;
+    let r2 = to_working_precision(r2, prime_bits)?;
+    let one = T::one_like(&r1);
+
+    // Step 2: find `R` such that `R = 1 mod 2 * r1` and `R = -1 mod r2`.
+    // We construct it modulo `r1 * r2` first: `R = r2 * (2 * r2^(-1) mod r1) - 1`.
+    // Since `r1` is prime, the inverse can be calculated as `r2^(r1 - 2) mod r1`.
+    let r1_odd = Odd::new(r1.clone()).expect("`r1` is an odd prime");
+    let params = <T as Unsigned>::Monty::new_params_vartime(r1_odd.clone());
+    let r2_mod_r1 = r2.clone() % r1_odd.as_nz_ref();
+    let r2_mod_r1 = <T as Unsigned>::Monty::new(r2_mod_r1, params);
+    let exponent = r1.wrapping_sub(&T::from_limb_like(2u32.into(), &r1));
+    let r2_inv = r2_mod_r1.pow_bounded_exp(&exponent, exponent.bits_vartime());
+    let u = (r2_inv.clone() + &r2_inv).retrieve();
+    let r1_r2 = r1.wrapping_mul(&r2);
+    let mut cap_r = r2.wrapping_mul(&u).wrapping_sub(&one);
+    // Now make it odd, which makes it equal to 1 modulo 2.
+    if cap_r.is_even().into() {
+        cap_r = cap_r.wrapping_add(&r1_r2);
+    }
+    let modulus = CTNonZero::new(r1_r2.wrapping_shl_vartime(1)).expect("`r1` and `r2` are non-zero");
+
+    let bit_length = NonZero::new(prime_bits).expect("`prime_bits` is non-zero by construction");
+    'outer: loop {
+        // Step 3: X is a random number in `[sqrt(2) * 2^(prime_bits - 1), 2^prime_bits)`.
+        // The oddness of X is irrelevant since Y is fixed modulo 2 by `R`.
+        let x = random_odd_integer::<T, _>(rng, bit_length, SetBits::Msb)?.get();
+        if !above_sqrt2_bound(&x, prime_bits) {
+            continue;
+        }
+
+        // Step 4: Y = X + ((R - X) mod 2 * r1 * r2)
+        let x_mod = x.clone() % &modulus;
+        let offset = cap_r.sub_mod(&x_mod, &modulus);
+        let Some(mut y) = x.checked_add(&offset).into_option() else {
+            continue;
+        };
+
+        let mut attempts = 0;
+        loop {
+            // Step 6
+            if y.bits_vartime() > prime_bits {
+                continue 'outer;
+            }
+
+            // Step 7
+            let y_minus_one = y.wrapping_sub(&one);
+            if gcd_big_vartime(&y_minus_one, e) == one && is_prime(rng, Flavor::Any, &y, mr_iterations, false) {
+                return Ok((y, x));
+            }
+
+            // Step 8
+            attempts += 1;
+            if attempts >= max_attempts {
+                return Err(Error::AttemptsExhausted { attempts });
+            }
+
+            // Step 9
+            y = match y.checked_add(&modulus).into_option() {
+                Some(y) => y,
+                None => continue 'outer,
+            };
+        }
+    }
+}
+
+/// Returns `true` if `x >= sqrt(2) * 2^(bit_length - 1)`, where `x` is `bit_length` bits long.
+///
+/// Only the top 64 bits are compared, so a negligible fraction of numbers right above the bound
+/// is rejected too; this does not affect the guarantee on the lower bound.
+fn above_sqrt2_bound<T: Unsigned>(x: &T, bit_length: u32) -> bool {
+    debug_assert!(bit_length >= 64);
+    let top_bits = x.wrapping_shr_vartime(bit_length - 64);
+    top_bits > T::from(SQRT_2_TOP_BITS)
+}
+
+/// Returns `true` if `|x - y| > 2^log2_bound`.
+fn differ_enough<T: Unsigned>(x: &T, y: &T, log2_bound: u32) -> bool {
+    let diff = if x > y { x.wrapping_sub(y) } else { y.wrapping_sub(x) };
+    let diff_bits = diff.bits_vartime();
+    diff_bits > log2_bound + 1 || (diff_bits == log2_bound + 1 && diff.trailing_zeros_vartime() != log2_bound)
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto_bigint::{BoxedUint, NonZero, RandomMod, U64, U1024, U2048, Unsigned};
+    use rand::rngs::ChaCha8Rng;
+    use rand_core::SeedableRng;
+
+    use super::{
+        RsaPrimeWithConditions, differ_enough, generate_aux_prime, generate_prime, generate_rsa_primes,
+        generate_rsa_primes_with_conditions,
+    };
+    use crate::{Error, Flavor, hazmat::gcd_big_vartime, is_prime};
+
+    fn check_rsa_prime<T: Unsigned + RandomMod>(p: &T, nlen: u32, e: &T) {
+        assert_eq!(p.bits_vartime(), nlen / 2);
+        assert!(is_prime(Flavor::Any, p));
+        let p_minus_one = p.wrapping_sub(&T::one_like(p));
+        assert_eq!(gcd_big_vartime(&p_minus_one, e), T::one_like(p));
+    }
+
+    #[test]
+    fn rsa_primes() {
+        let mut rng = rand::rng();
+        let e = U1024::from(65537u32);
+        let (p, q) = generate_rsa_primes(&mut rng, 2048, &e).unwrap();
+
+        check_rsa_prime(&p, 2048, &e);
+        check_rsa_prime(&q, 2048, &e);
+        assert!(differ_enough(&p, &q, 1024 - 100));
+
+        // The modulus must have the full requested size.
+        let n = p.concatenating_mul(&q);
+        assert_eq!(n.bits_vartime(), 2048);
+    }
+
+    #[test]
+    fn rsa_primes_boxed() {
+        let mut rng = rand::rng();
+        let e = BoxedUint::from(65537u32);
+        let (p, q) = generate_rsa_primes(&mut rng, 2048, &e).unwrap();
+
+        check_rsa_prime(&p, 2048, &e);
+        check_rsa_prime(&q, 2048, &e);
+        assert!(differ_enough(&p, &q, 1024 - 100));
+    }
+
+    fn check_rsa_prime_with_conditions<T: Unsigned + RandomMod>(
+        prime: &RsaPrimeWithConditions<T>,
+        nlen: u32,
+        e: &T,
+        aux_prime_bits: u32,
+    ) {
+        let RsaPrimeWithConditions { prime, aux1, aux2 } = prime;
+        check_rsa_prime(prime, nlen, e);
+
+        for aux in [aux1, aux2] {
+            assert_eq!(aux.bits_vartime(), aux_prime_bits);
+            assert!(is_prime(Flavor::Any, aux));
+        }
+
+        let p_minus_one = prime.wrapping_sub(&T::one_like(prime));
+        let p_plus_one = prime.wrapping_add(&T::one_like(prime));
+        assert!(bool::from(
+            (p_minus_one % NonZero::new(aux1.clone()).unwrap()).is_zero()
+        ));
+        assert!(bool::from((p_plus_one % NonZero::new(aux2.clone()).unwrap()).is_zero()));
+    }
+
+    #[test]
+    fn rsa_primes_with_conditions() {
+        let mut rng = rand::rng();
+        let e = U1024::from(65537u32);
+        let (p, q) = generate_rsa_primes_with_conditions(&mut rng, 2048, &e, 200).unwrap();
+
+        check_rsa_prime_with_conditions(&p, 2048, &e, 200);
+        check_rsa_prime_with_conditions(&q, 2048, &e, 200);
+        assert!(differ_enough(&p.prime, &q.prime, 1024 - 100));
+    }
+
+    #[test]
+    fn rsa_primes_with_conditions_boxed() {
+        let mut rng = rand::rng();
+        let e = BoxedUint::from(65537u32);
+        let (p, q) = generate_rsa_primes_with_conditions(&mut rng, 2048, &e, 141).unwrap();
+
+        check_rsa_prime_with_conditions(&p, 2048, &e, 141);
+        check_rsa_prime_with_conditions(&q, 2048, &e, 141);
+        assert!(differ_enough(&p.prime, &q.prime, 1024 - 100));
+    }
+
+    #[test]
+    fn invalid_aux_prime_bits() {
+        let mut rng = rand::rng();
+        let e = U2048::from(65537u32);
+        for (nlen, aux_prime_bits) in [
+            (2048, 140),
+            (2048, 504),
+            (3072, 170),
+            (3072, 759),
+            (4096, 200),
+            (4096, 1015),
+        ] {
+            assert_eq!(
+                generate_rsa_primes_with_conditions(&mut rng, nlen, &e, aux_prime_bits).unwrap_err(),
+                Error::InvalidParameter { name: "aux_prime_bits" }
+            );
+        }
+        assert_eq!(
+            generate_rsa_primes_with_conditions(&mut rng, 2047, &e, 200).unwrap_err(),
+            Error::InvalidParameter { name: "nlen" }
+        );
+    }
+
+    #[test]
+    fn invalid_nlen() {
+        let mut rng = rand::rng();
+        let e = U1024::from(65537u32);
+        assert_eq!(
+            generate_rsa_primes(&mut rng, 1024, &e).unwrap_err(),
+            Error::InvalidParameter { name: "nlen" }
+        );
+        assert_eq!(
+            generate_rsa_primes(&mut rng, 2049, &e).unwrap_err(),
+            Error::InvalidParameter { name: "nlen" }
+        );
+        assert_eq!(
+            generate_rsa_primes(&mut rng, 4096, &e).unwrap_err(),
+            Error::BitLengthTooLarge {
+                bit_length: 2048,
+                bits_precision: 1024
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_e() {
+        let mut rng = rand::rng();
+        for e in [
+            U1024::from(3u32),
+            U1024::from(65536u32),
+            U1024::from(65538u32),
+            U1024::ONE.wrapping_shl_vartime(256).wrapping_add(&U1024::ONE),
+        ] {
+            assert_eq!(
+                generate_rsa_primes(&mut rng, 2048, &e).unwrap_err(),
+                Error::InvalidParameter { name: "e" }
+            );
+        }
+    }
+
+    #[test]
+    fn attempts_exhausted() {
+        // With this seed the first candidate is composite.
+        let mut rng = ChaCha8Rng::from_seed(*b"01234567890123456789012345678901");
+        let e = U1024::from(65537u32);
+        assert_eq!(
+            generate_prime(&mut rng, 1024, &e, 5, None, 1).unwrap_err(),
+            Error::AttemptsExhausted { attempts: 1 }
+        );
+        assert_eq!(
+            generate_aux_prime::<U1024, _>(&mut rng, 200, 5, 0).unwrap_err(),
+            Error::AttemptsExhausted { attempts: 0 }
+        );
+    }
+
+    #[test]
+    fn differ_enough_bound() {
+        let x = U2048::ONE.wrapping_shl_vartime(200);
+        let bound = U2048::ONE.wrapping_shl_vartime(100);
+        assert!(!differ_enough(&x, &x.wrapping_add(&bound), 100));
+        assert!(differ_enough(
+            &x,
+            &x.wrapping_add(&bound).wrapping_add(&U2048::ONE),
+            100
+        ));
+        assert!(differ_enough(&x.wrapping_add(&bound.wrapping_shl_vartime(1)), &x, 100));
+        assert!(!differ_enough(&U64::ONE, &U64::ONE, 0));
+    }
+}
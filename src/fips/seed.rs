@@ -0,0 +1,94 @@
+//! Deriving integers from seeds with an approved hash function,
+//! as used by the seed-based procedures of FIPS-186.
+
+use alloc::vec::Vec;
+
+use crypto_bigint::Unsigned;
+
+/// Returns `hash(seed + i)` for `i` in `0..=iterations`, and advances the seed by `iterations + 1`.
+pub(crate) fn hash_blocks<H>(seed: &mut [u8], iterations: u32, hash: &impl Fn(&[u8]) -> H) -> Vec<H> {
+    let mut blocks = Vec::with_capacity(iterations as usize + 1);
+    for _ in 0..=iterations {
+        blocks.push(hash(seed));
+        increment_seed(seed, 1);
+    }
+    blocks
+}
+
+/// Returns the bits of a big-endian integer as pairs `(position, value)`, starting from the least significant one.
+pub(crate) fn block_bits(block: &[u8]) -> impl DoubleEndedIterator<Item = (u32, bool)> + '_ {
+    block.iter().rev().enumerate().flat_map(|(byte_idx, byte)| {
+        (0..8).map(move |bit_idx| ((byte_idx * 8) as u32 + bit_idx, (byte >> bit_idx) & 1 == 1))
+    })
+}
+
+/// Returns `2^(bit_length - 1) + (W mod 2^(bit_length - 1))`, where `W = sum(blocks[i] * 2^(i * outlen))`,
+/// with the precision of `zero`.
+///
+/// The blocks are interpreted as big-endian integers of `outlen` bits each.
+pub(crate) fn integer_from_blocks<T: Unsigned, H: AsRef<[u8]>>(
+    zero: &T,
+    blocks: &[H],
+    outlen: u32,
+    bit_length: u32,
+) -> T {
+    let mut x = zero.clone();
+    for (i, block) in blocks.iter().enumerate() {
+        for (j, bit) in block_bits(block.as_ref()) {
+            let position = i as u32 * outlen + j;
+            if position < bit_length - 1 && bit {
+                x.set_bit_vartime(position, true);
+            }
+        }
+    }
+    x.set_bit_vartime(bit_length - 1, true);
+    x
+}
+
+/// Adds `value` to the big-endian integer `seed`, modulo `2^(8 * seed.len())`.
+pub(crate) fn increment_seed(seed: &mut [u8], value: u32) {
+    let mut carry = value;
+    for byte in seed.iter_mut().rev() {
+        if carry == 0 {
+            break;
+        }
+        let sum = u32::from(*byte) + (carry & 0xff);
+        *byte = sum as u8;
+        carry = (carry >> 8) + (sum >> 8);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto_bigint::U128;
+
+    use super::{increment_seed, integer_from_blocks};
+
+    #[test]
+    fn seed_increment() {
+        let mut seed = [0x00, 0xff, 0xfe];
+        increment_seed(&mut seed, 2);
+        assert_eq!(seed, [0x01, 0x00, 0x00]);
+
+        let mut seed = [0xff, 0xff];
+        increment_seed(&mut seed, 1);
+        assert_eq!(seed, [0x00, 0x00]);
+
+        let mut seed = [0x00, 0x12, 0xff, 0xff];
+        increment_seed(&mut seed, 0x0102_0304);
+        assert_eq!(seed, [0x01, 0x15, 0x03, 0x03]);
+    }
+
+    #[test]
+    fn integer_from_hash_blocks() {
+        let blocks = [[0x12, 0x34], [0xff, 0xff]];
+        assert_eq!(
+            integer_from_blocks(&U128::ZERO, &blocks, 16, 24),
+            U128::from(0x00ff_1234u32 | 0x0080_0000)
+        );
+        assert_eq!(
+            integer_from_blocks(&U128::ZERO, &blocks, 16, 8),
+            U128::from(0x34u32 & 0x7f | 0x80)
+        );
+    }
+}
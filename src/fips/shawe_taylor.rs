@@ -0,0 +1,399 @@
+//! Provable prime generation with the Shawe-Taylor algorithm.
+
+use alloc::vec::Vec;
+
+use crypto_bigint::{Monty, NonZero as CTNonZero, Odd, PowBoundedExp, RandomBits, Unsigned, modular::Retrieve};
+
+use super::seed::{block_bits, hash_blocks, increment_seed, integer_from_blocks};
+use crate::{
+    error::Error,
+    hazmat::{gcd_big_vartime, zero_with_bit_length},
+};
+
+/// The largest bit length for which the prime is found by trial division.
+const MAX_TRIAL_DIVISION_BITS: u32 = 32;
+
+/// A prime generated by [`shawe_taylor_random_prime`].
+///
+/// Running [`shawe_taylor_random_prime`] with the same input seed, bit length and hash function
+/// reproduces all the fields exactly, which is how the primality of the result can be verified
+/// by a third party.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaweTaylorPrime<T> {
+    /// The generated prime.
+    pub prime: T,
+    /// The seed value at the end of the generation,
+    /// to be used as the input seed for the subsequent steps of a larger procedure, if there are any.
+    pub prime_seed: Vec<u8>,
+    /// The number of candidates tested during the generation (including the recursive steps).
+    pub prime_gen_counter: u32,
+}
+
+/// Deterministically generates a provable prime of exactly `bit_length` bits from `seed`
+/// with the Shawe-Taylor algorithm, as prescribed by FIPS-186.5[^FIPS], Appendix A.1.2
+/// (the routine itself is specified in FIPS-186.4[^FIPS-186.4], Appendix C.6).
+///
+/// `hash` is the approved hash function used to expand the seed (e.g. SHA-256);
+/// its output is interpreted as a big-endian integer.
+/// The seed is interpreted as a big-endian integer as well, and is incremented modulo `2^(8 * seed.len())`.
+///
+/// Unlike the probabilistic tests in this crate, the primality of the result is guaranteed:
+/// primes of up to 32 bits are checked by trial division, and the larger ones
+/// are recursively built from smaller primes with the Pocklington criterion.
+///
+/// Returns an error if `bit_length < 2`, if `seed` is empty, if `hash` produces fewer than 4 bytes,
+/// or if `bit_length` is larger than the precision of `T` (for fixed-size integers).
+/// If the algorithm does not find a prime within the number of candidates allowed by the standard,
+/// [`Error::AttemptsExhausted`] is returned; the caller should then try another seed.
+///
+/// [^FIPS]: FIPS-186.5 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf>
+///
+/// [^FIPS-186.4]: FIPS-186.4 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-4.pdf>
+pub fn shawe_taylor_random_prime<T, H>(
+    seed: &[u8],
+    bit_length: u32,
+    hash: impl Fn(&[u8]) -> H,
+) -> Result<ShaweTaylorPrime<T>, Error>
+where
+    T: Unsigned + RandomBits,
+    H: AsRef<[u8]>,
+{
+    if bit_length < 2 {
+        return Err(Error::InvalidParameter { name: "bit_length" });
+    }
+    if seed.is_empty() {
+        return Err(Error::InvalidParameter { name: "seed" });
+    }
+    if hash(seed).as_ref().len() < 4 {
+        return Err(Error::InvalidParameter { name: "hash" });
+    }
+
+    // Check that the result will fit in `T` before doing any work.
+    zero_with_bit_length::<T>(bit_length)?;
+
+    random_prime(seed, bit_length, &hash)
+}
+
+fn random_prime<T, H>(seed: &[u8], length: u32, hash: &impl Fn(&[u8]) -> H) -> Result<ShaweTaylorPrime<T>, Error>
+where
+    T: Unsigned + RandomBits,
+    H: AsRef<[u8]>,
+{
+    if length <= MAX_TRIAL_DIVISION_BITS {
+        return random_small_prime(seed, length, hash);
+    }
+
+    // Steps 14-15: recursively generate a prime of half the size.
+    let ShaweTaylorPrime {
+        prime: c0,
+        prime_seed,
+        prime_gen_counter,
+    } = random_prime::<T, H>(seed, length.div_ceil(2) + 1, hash)?;
+    let mut prime_seed = prime_seed;
+    let mut prime_gen_counter = prime_gen_counter;
+
+    // Bring `c0` to the working precision (only matters for boxed integers).
+    let zero = zero_with_bit_length::<T>(length)?;
+    let c0 = zero.wrapping_add(&c0);
+    let one = T::one_like(&zero);
+
+    // Steps 16-21: x = 2^(length - 1) + (hash(prime_seed) mod 2^(length - 1))
+    let outlen = hash(&prime_seed).as_ref().len() as u32 * 8;
+    let iterations = length.div_ceil(outlen) - 1;
+    let old_counter = prime_gen_counter;
+
+    let x = integer_from_blocks(&zero, &hash_blocks(&mut prime_seed, iterations, hash), outlen, length);
+
+    // Step 22: t = ceil(x / (2 * c0))
+    let two_c0 = CTNonZero::new(c0.wrapping_shl_vartime(1)).expect("`c0` is a prime and therefore non-zero");
+    let mut t = div_ceil(&x, &two_c0);
+
+    loop {
+        // Steps 23-24: c = 2 * t * c0 + 1, restarting from the lower bound of the range if it is too large.
+        let c = match two_c0_t_plus_one(&t, &two_c0, length) {
+            Some(c) => c,
+            None => {
+                let mut lower_bound = zero.clone();
+                lower_bound.set_bit_vartime(length - 1, true);
+                t = div_ceil(&lower_bound, &two_c0);
+                two_c0_t_plus_one(&t, &two_c0, length).expect("the lower bound of the range fits by construction")
+            }
+        };
+
+        // Step 25
+        prime_gen_counter += 1;
+
+        // Steps 26-29: a = 2 + (hash(prime_seed) mod (c - 3))
+        let c_minus_three = CTNonZero::new(c.wrapping_sub(&T::from_limb_like(3u32.into(), &c)))
+            .expect("`c` is greater than `2^32` by construction");
+        let blocks = hash_blocks(&mut prime_seed, iterations, hash);
+        let mut a = zero.clone();
+        for block in blocks.iter().rev() {
+            for (_, bit) in block_bits(block.as_ref()).rev() {
+                a = a.add_mod(&a, &c_minus_three);
+                if bit {
+                    a = a.add_mod(&one, &c_minus_three);
+                }
+            }
+        }
+        let a = a.wrapping_add(&T::from_limb_like(2u32.into(), &a));
+
+        // Steps 30-31: the Pocklington criterion, with `c0` being the large prime factor of `c - 1`.
+        let params =
+            <T as Unsigned>::Monty::new_params_vartime(Odd::new(c.clone()).expect("`c` is odd by construction"));
+        let a = <T as Unsigned>::Monty::new(a, params.clone());
+        let monty_one = <T as Unsigned>::Monty::one(params);
+        let two_t = t.wrapping_shl_vartime(1);
+        let z = a.pow_bounded_exp(&two_t, two_t.bits_vartime());
+        let z_minus_one = (z.clone() - &monty_one).retrieve();
+        if gcd_big_vartime(&z_minus_one, &c) == one && z.pow_bounded_exp(&c0, c0.bits_vartime()) == monty_one {
+            return Ok(ShaweTaylorPrime {
+                prime: c,
+                prime_seed,
+                prime_gen_counter,
+            });
+        }
+
+        // Step 32
+        if prime_gen_counter >= 4 * length + old_counter {
+            return Err(Error::AttemptsExhausted {
+                attempts: prime_gen_counter as usize,
+            });
+        }
+
+        // Step 33
+        t = t.wrapping_add(&one);
+    }
+}
+
+/// Steps 3-13 of the Shawe-Taylor routine: finds a prime of at most 32 bits by trial division.
+fn random_small_prime<T, H>(seed: &[u8], length: u32, hash: &impl Fn(&[u8]) -> H) -> Result<ShaweTaylorPrime<T>, Error>
+where
+    T: Unsigned + RandomBits,
+    H: AsRef<[u8]>,
+{
+    let mut prime_seed = Vec::from(seed);
+    let mut prime_gen_counter = 0;
+
+    loop {
+        // Step 5: c = hash(prime_seed) xor hash(prime_seed + 1)
+        let h1 = hash(&prime_seed);
+        let mut seed_plus_one = prime_seed.clone();
+        increment_seed(&mut seed_plus_one, 1);
+        let h2 = hash(&seed_plus_one);
+        let c = h1
+            .as_ref()
+            .iter()
+            .rev()
+            .zip(h2.as_ref().iter().rev())
+            .take(4)
+            .rev()
+            .fold(0u32, |acc, (b1, b2)| (acc << 8) | (b1 ^ b2) as u32);
+
+        // Steps 6-7: c = 2^(length - 1) + (c mod 2^(length - 1)), made odd
+        let c = ((1u64 << (length - 1)) | (c as u64 & ((1u64 << (length - 1)) - 1)) | 1) as u32;
+
+        // Steps 8-9
+        prime_gen_counter += 1;
+        increment_seed(&mut prime_seed, 2);
+
+        // Steps 10-11
+        if is_prime_trial_division(c) {
+            let mut prime = zero_with_bit_length::<T>(length)?;
+            prime = prime.wrapping_add(&T::from(c));
+            return Ok(ShaweTaylorPrime {
+                prime,
+                prime_seed,
+                prime_gen_counter,
+            });
+        }
+
+        // Step 12
+        if prime_gen_counter > 4 * length {
+            return Err(Error::AttemptsExhausted {
+                attempts: prime_gen_counter as usize,
+            });
+        }
+    }
+}
+
+/// Returns `ceil(x / y)`.
+fn div_ceil<T: Unsigned>(x: &T, y: &CTNonZero<T>) -> T {
+    let quotient = x.clone() / y;
+    if (x.clone() % y).is_zero().into() {
+        quotient
+    } else {
+        quotient.wrapping_add(&T::one_like(x))
+    }
+}
+
+/// Returns `2 * c0 * t + 1` if it is not greater than `2^length`, and `None` otherwise.
+fn two_c0_t_plus_one<T: Unsigned>(t: &T, two_c0: &CTNonZero<T>, length: u32) -> Option<T> {
+    let c = t.checked_mul(two_c0.as_ref()).into_option()?;
+    let c = c.checked_add(&T::one_like(t)).into_option()?;
+    // `c` is odd, so it cannot be equal to `2^length`.
+    if c.bits_vartime() > length { None } else { Some(c) }
+}
+
+/// Checks if `n` is prime by dividing it by all the odd numbers up to `sqrt(n)`.
+fn is_prime_trial_division(n: u32) -> bool {
+    if n < 2 {
+        return false;
+    }
+    if n < 4 {
+        return true;
+    }
+    if n % 2 == 0 {
+        return false;
+    }
+    let n = n as u64;
+    let mut d = 3;
+    while d * d <= n {
+        if n % d == 0 {
+            return false;
+        }
+        d += 2;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto_bigint::{BoxedUint, NonZero, U64, U128, U256, U1024, U2048};
+    use num_prime::nt_funcs::is_prime64;
+    use sha2::{Digest, Sha256};
+
+    use super::{ShaweTaylorPrime, is_prime_trial_division, shawe_taylor_random_prime};
+    use crate::{Error, Flavor, is_prime};
+
+    fn sha256(data: &[u8]) -> [u8; 32] {
+        Sha256::digest(data).into()
+    }
+
+    #[test]
+    fn small_primes() {
+        for bit_length in 2..=32 {
+            let result = shawe_taylor_random_prime::<U64, _>(b"small prime seed", bit_length, sha256).unwrap();
+            assert_eq!(result.prime.bits_vartime(), bit_length);
+            assert!(is_prime64(result.prime.as_words()[0]));
+        }
+    }
+
+    #[test]
+    fn large_primes() {
+        for bit_length in [33, 64, 100, 128] {
+            let result = shawe_taylor_random_prime::<U128, _>(b"large prime seed", bit_length, sha256).unwrap();
+            assert_eq!(result.prime.bits_vartime(), bit_length);
+            assert!(is_prime(Flavor::Any, &result.prime));
+        }
+
+        let result = shawe_taylor_random_prime::<U1024, _>(b"1024-bit prime seed", 1024, sha256).unwrap();
+        assert_eq!(result.prime.bits_vartime(), 1024);
+        assert!(is_prime(Flavor::Any, &result.prime));
+    }
+
+    #[test]
+    fn large_primes_boxed() {
+        for bit_length in [33, 64, 100, 128, 300] {
+            let result = shawe_taylor_random_prime::<BoxedUint, _>(b"large prime seed", bit_length, sha256).unwrap();
+            assert_eq!(result.prime.bits_vartime(), bit_length);
+            assert!(is_prime(Flavor::Any, &result.prime));
+        }
+    }
+
+    #[test]
+    fn reproducible() {
+        let seed = b"reproducible seed";
+        let result = shawe_taylor_random_prime::<U1024, _>(seed, 512, sha256).unwrap();
+
+        // Verification is just running the algorithm again.
+        assert_eq!(
+            shawe_taylor_random_prime::<U1024, _>(seed, 512, sha256),
+            Ok(result.clone())
+        );
+
+        let boxed = shawe_taylor_random_prime::<BoxedUint, _>(seed, 512, sha256).unwrap();
+        assert_eq!(&*boxed.prime.to_be_bytes(), &result.prime.to_be_bytes()[64..]);
+        assert_eq!(boxed.prime_seed, result.prime_seed);
+        assert_eq!(boxed.prime_gen_counter, result.prime_gen_counter);
+
+        // A different seed produces a different prime.
+        let ShaweTaylorPrime { prime, .. } =
+            shawe_taylor_random_prime::<U1024, _>(b"another seed", 512, sha256).unwrap();
+        assert_ne!(prime, result.prime);
+    }
+
+    #[test]
+    fn known_answer() {
+        // Domain parameters generated by NSS (`PK11_PQG_ParamGenV2(2048, 256, 32)`), an independent implementation
+        // of the provable generation of FIPS-186.4, Appendix A.1.2.1.2, with SHA-256.
+        // `q` is the C.6 output for `first_seed`, and `p` is built on the C.6 output `p0` for the seed it returned.
+        let first_seed = U256::from_be_hex("df64a0552b24d23ef04a9127fa26ef1ea9e425ce85b4bd75852ddd1bbffefcf0");
+        let q_seed = U256::from_be_hex("df64a0552b24d23ef04a9127fa26ef1ea9e425ce85b4bd75852ddd1bbffefe35");
+        let q = U256::from_be_hex("ff92198f2a72a2b3cbab72f0b50c1f351e237d69ec6aba911296022f444a0207");
+        let p = U2048::from_be_hex(concat![
+            "ea02760567d927fb41e66eb44ac93275e5a1df18eb1f1d52a9442bfd27df27d79bd21d236998cbdd2a36ff13548065b1",
+            "829212a45a7092ad6e4458f07840a2efa472387af4d8ff0c921db24a60bb28681a1e40b0810a08841703b676e3ae4fa6",
+            "812be09b1d19bdfdf929b394fdfdd7185769f8e98beecf085cc884dc5b53bef0f0ad94364ad9d1023889df3de6917e2e",
+            "e3267986afa7cad9b9d714a085c50584d4c5502ddd1c754bb3e90f322963db4039d0696ab59605e7013b178f58f70cfd",
+            "5d2428a3d90f25d5d553540d91741faca56bf49e2642d4009831b25253cd6e171636fae8f23ea1349497cca3a7b1eeb5",
+            "07604042e6439712e9b864007dd5ff93",
+        ]);
+
+        let result = shawe_taylor_random_prime::<U256, _>(&first_seed.to_be_bytes(), 256, sha256).unwrap();
+        assert_eq!(result.prime, q);
+        assert_eq!(result.prime_seed, q_seed.to_be_bytes());
+        assert_eq!(result.prime_gen_counter, 306);
+
+        let p0 = shawe_taylor_random_prime::<U2048, _>(&q_seed.to_be_bytes(), 1025, sha256).unwrap();
+        assert_eq!(p0.prime.bits_vartime(), 1025);
+        assert_eq!(
+            p0.prime_seed,
+            U256::from_be_hex("df64a0552b24d23ef04a9127fa26ef1ea9e425ce85b4bd75852ddd1bbfff0646").to_be_bytes()
+        );
+        assert_eq!(p0.prime_gen_counter, 656);
+        let p_minus_one = p.wrapping_sub(&U2048::ONE);
+        assert_eq!(p_minus_one.rem_vartime(&NonZero::new(p0.prime).unwrap()), U2048::ZERO);
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert_eq!(
+            shawe_taylor_random_prime::<U64, _>(b"seed", 1, sha256),
+            Err(Error::InvalidParameter { name: "bit_length" })
+        );
+        assert_eq!(
+            shawe_taylor_random_prime::<U64, _>(b"", 32, sha256),
+            Err(Error::InvalidParameter { name: "seed" })
+        );
+        assert_eq!(
+            shawe_taylor_random_prime::<U64, _>(b"seed", 32, |_data: &[u8]| [0u8; 3]),
+            Err(Error::InvalidParameter { name: "hash" })
+        );
+        assert_eq!(
+            shawe_taylor_random_prime::<U64, _>(b"seed", 65, sha256),
+            Err(Error::BitLengthTooLarge {
+                bit_length: 65,
+                bits_precision: 64
+            })
+        );
+    }
+
+    #[test]
+    fn attempts_exhausted() {
+        // A constant hash always produces the same candidate.
+        assert_eq!(
+            shawe_taylor_random_prime::<U64, _>(b"seed", 8, |_data: &[u8]| [0u8; 4]),
+            Err(Error::AttemptsExhausted { attempts: 33 })
+        );
+    }
+
+    #[test]
+    fn trial_division() {
+        for n in 0..10000u32 {
+            assert_eq!(is_prime_trial_division(n), is_prime64(n.into()), "n={n}");
+        }
+        assert!(is_prime_trial_division(4294967291));
+        assert!(!is_prime_trial_division(4294967295));
+    }
+}
@@ -1,6 +1,10 @@
 use rand_core::CryptoRng;
 
-use crate::{Error, hazmat::SieveFactory};
+use crate::{
+    Error,
+    budget::{Budget, BudgetTracker},
+    hazmat::SieveFactory,
+};
 
 /// Sieves through the results of `sieve_factory` and returns the first item for which `predicate` is `true`.
 ///
@@ -10,6 +14,21 @@ pub fn sieve_and_find<R, S>(
     sieve_factory: S,
     predicate: impl Fn(&mut R, &S::Item) -> bool,
 ) -> Result<Option<S::Item>, Error>
+where
+    S: SieveFactory,
+    R: CryptoRng + ?Sized,
+{
+    sieve_and_find_with_budget(rng, sieve_factory, predicate, Budget::unlimited())
+}
+
+/// Same as [`sieve_and_find`], but stops with [`Error::AttemptsExhausted`]
+/// when the limits set by `budget` are reached.
+pub fn sieve_and_find_with_budget<R, S>(
+    rng: &mut R,
+    sieve_factory: S,
+    predicate: impl Fn(&mut R, &S::Item) -> bool,
+    budget: Budget<'_>,
+) -> Result<Option<S::Item>, Error>
 where
     S: SieveFactory,
     R: CryptoRng + ?Sized,
@@ -17,16 +36,22 @@ where
     // We could use `SieveIterator` here, but it requires cloning the `rng`.
     // Unlike the parallel version, it is avoidable here.
 
+    let mut tracker = BudgetTracker::new(budget);
     let mut sieve_factory = sieve_factory;
+    tracker.add_sieve()?;
     let mut sieve = match sieve_factory.make_sieve(rng, None)? {
         Some(sieve) => sieve,
         None => return Ok(None),
     };
 
     loop {
-        if let Some(value) = sieve.find(|num| predicate(rng, num)) {
-            return Ok(Some(value));
+        for value in sieve.by_ref() {
+            tracker.add_candidate()?;
+            if predicate(rng, &value) {
+                return Ok(Some(value));
+            }
         }
+        tracker.add_sieve()?;
         if let Some(new_sieve) = sieve_factory.make_sieve(rng, Some(&sieve))? {
             sieve = new_sieve;
         } else {
@@ -39,8 +64,8 @@ where
 mod tests {
     use rand_core::CryptoRng;
 
-    use super::sieve_and_find;
-    use crate::{Error, hazmat::SieveFactory};
+    use super::{sieve_and_find, sieve_and_find_with_budget};
+    use crate::{Budget, Error, hazmat::SieveFactory};
 
     #[test]
     fn test_exhaustable_sieve_factory() {
@@ -77,4 +102,43 @@ mod tests {
         let result = sieve_and_find(&mut rng, factory, |_rng, num| *num == 20);
         assert!(result.unwrap().is_none());
     }
+
+    #[test]
+    fn budget() {
+        struct TestSieveFactory;
+
+        impl SieveFactory for TestSieveFactory {
+            type Item = usize;
+            type Sieve = core::ops::Range<usize>;
+
+            fn make_sieve<R: CryptoRng + ?Sized>(
+                &mut self,
+                _rng: &mut R,
+                previous_sieve: Option<&Self::Sieve>,
+            ) -> Result<Option<Self::Sieve>, Error> {
+                let start = previous_sieve.map_or(0, |sieve| sieve.end);
+                Ok(Some(start..start + 10))
+            }
+        }
+
+        let mut rng = rand::rng();
+
+        // The 25th candidate is in the third sieve.
+        let budget = Budget::unlimited().with_max_candidates(25).with_max_sieves(3);
+        let result = sieve_and_find_with_budget(&mut rng, TestSieveFactory, |_rng, num| *num == 24, budget);
+        assert_eq!(result, Ok(Some(24)));
+
+        let budget = Budget::unlimited().with_max_candidates(24);
+        let result = sieve_and_find_with_budget(&mut rng, TestSieveFactory, |_rng, num| *num == 24, budget);
+        assert_eq!(result, Err(Error::AttemptsExhausted { attempts: 24 }));
+
+        let budget = Budget::unlimited().with_max_sieves(2);
+        let result = sieve_and_find_with_budget(&mut rng, TestSieveFactory, |_rng, num| *num == 24, budget);
+        assert_eq!(result, Err(Error::AttemptsExhausted { attempts: 20 }));
+
+        let deadline = || true;
+        let budget = Budget::unlimited().with_deadline(&deadline);
+        let result = sieve_and_find_with_budget(&mut rng, TestSieveFactory, |_rng, num| *num == 24, budget);
+        assert_eq!(result, Err(Error::AttemptsExhausted { attempts: 0 }));
+    }
 }
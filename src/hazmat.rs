@@ -1,10 +1,16 @@
 //! Components to build your own primality test.
 //! Handle with care.
 
+mod congruence_sieve;
+mod ct;
+mod cunningham_sieve;
+mod eratosthenes;
 mod float;
 mod gcd;
 mod jacobi;
+mod joye_paillier;
 mod lucas;
+mod meissel_lehmer;
 mod miller_rabin;
 mod precomputed;
 mod primecount;
@@ -12,12 +18,31 @@ mod primecount;
 pub(crate) mod primes;
 #[cfg(test)]
 pub(crate) mod pseudoprimes;
+mod range_sieve;
 mod sieve;
+mod uniform;
+mod wipe;
 
-pub use lucas::{AStarBase, BruteForceBase, LucasBase, LucasCheck, SelfridgeBase, lucas_test};
+pub use congruence_sieve::{CongruenceSieve, CongruenceSieveFactory};
+pub use ct::{lucas_test_ct, miller_rabin_ct};
+pub use cunningham_sieve::{CunninghamSieve, CunninghamSieveFactory};
+pub use eratosthenes::{SegmentedSieve, small_odd_primes};
+pub use joye_paillier::{JoyePaillierFactory, JoyePaillierSieve};
+pub use lucas::{AStarBase, BruteForceBase, LucasBase, LucasCheck, SelfridgeBase, lucas_test, try_lucas_test};
+pub use meissel_lehmer::{nth_prime, primecount_exact};
 pub use miller_rabin::{MillerRabin, minimum_mr_iterations};
-pub use primecount::estimate_primecount;
+pub use primecount::{
+    estimate_inverse_li, estimate_nth_prime, estimate_primecount, primecount_bounds, primecount_bounds_rh,
+};
+pub use range_sieve::{RangeSieve, RangeSieveFactory};
 pub use sieve::{SetBits, SieveFactory, SmallFactorsSieve, SmallFactorsSieveFactory, random_odd_integer};
+pub use uniform::{FouqueTibouchiFactory, RejectionSamplingFactory};
+
+pub(crate) use float::two_powf_upper_bound;
+pub(crate) use gcd::gcd_big_vartime;
+pub(crate) use precomputed::SMALL_PRIMES;
+pub(crate) use sieve::{to_working_precision, zero_with_bit_length};
+pub(crate) use uniform::has_small_factor;
 
 use crypto_bigint::{Unsigned, Word};
 
@@ -0,0 +1,418 @@
+//! An iterator over the numbers in a given residue class,
+//! weeding out multiples of small primes before proceeding with slower tests.
+
+use core::num::NonZeroU32;
+
+use crypto_bigint::{NonZero as CTNonZero, RandomBits, Unsigned};
+use rand_core::CryptoRng;
+
+use super::{
+    gcd::gcd_big_vartime,
+    sieve::{ProgressionSieve, SetBits, SieveFactory, random_odd_integer},
+};
+use crate::{error::Error, presets::Flavor};
+
+/// An iterator returning odd numbers equal to `residue` modulo `modulus`,
+/// with up to and including given bit length, starting from a given number,
+/// that are not multiples of the first 2048 small primes.
+///
+/// Unlike [`SmallFactorsSieve`](`super::SmallFactorsSieve`), which only visits the numbers
+/// equal to 1 modulo 2 (or 3 modulo 4), the step of this sieve is `lcm(2, modulus)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CongruenceSieve<T: Unsigned> {
+    progression: ProgressionSieve<T>,
+}
+
+impl<T> CongruenceSieve<T>
+where
+    T: Unsigned,
+{
+    /// Creates a new sieve, iterating from `start` and until the last number with `max_bit_length` bits,
+    /// producing odd numbers equal to `residue` modulo `modulus` that are not non-trivial multiples
+    /// of a list of small primes in the range `[2, start)`.
+    ///
+    /// Returns an error if `max_bit_length` is greater than the precision of `start`,
+    /// if `modulus` is zero or has `max_bit_length` or more bits,
+    /// or if `residue` is not smaller than and coprime with `modulus`.
+    pub fn new(start: T, max_bit_length: NonZeroU32, residue: &T, modulus: &T) -> Result<Self, Error> {
+        Self::new_bounded(start, max_bit_length, None, residue, modulus)
+    }
+
+    // Same as `new`, but also stops at `max` (inclusive) if it is given.
+    fn new_bounded(
+        start: T,
+        max_bit_length: NonZeroU32,
+        max: Option<&T>,
+        residue: &T,
+        modulus: &T,
+    ) -> Result<Self, Error> {
+        let max_bit_length = max_bit_length.get();
+
+        if max_bit_length > start.bits_precision() {
+            return Err(Error::BitLengthTooLarge {
+                bit_length: max_bit_length,
+                bits_precision: start.bits_precision(),
+            });
+        }
+
+        let (residue, step) = odd_residue_class(residue, modulus, max_bit_length)?;
+
+        // Bring everything to the same precision (only matters for boxed integers).
+        let zero = T::zero_like(&start).wrapping_add(&T::zero_like(&step));
+        let start = zero.wrapping_add(&start);
+        let residue = zero.wrapping_add(&residue);
+        let step = CTNonZero::new(zero.wrapping_add(&step)).expect("the step is non-zero");
+
+        // `2^max_bit_length - 1`, calculated in a way that does not overflow.
+        let half = T::one_like(&zero).wrapping_shl_vartime(max_bit_length - 1);
+        let max_value = half.wrapping_sub(&T::one_like(&zero)).wrapping_add(&half);
+        let max_value = match max {
+            Some(max) if max < &max_value => zero.wrapping_add(max),
+            _ => max_value,
+        };
+
+        // The first number in the residue class that is not smaller than `start`.
+        let offset = residue.sub_mod(&(start.clone() % &step), &step);
+        let progression = match start.checked_add(&offset).into_option() {
+            Some(base) => ProgressionSieve::increasing(base, max_value, step.get()),
+            // The progression is empty.
+            None => ProgressionSieve::increasing(T::one_like(&zero), zero, step.get()),
+        };
+
+        Ok(Self { progression })
+    }
+
+    fn next(&mut self) -> Option<T> {
+        self.progression.next_by(|r, _d| r == 0)
+    }
+}
+
+impl<T> Iterator for CongruenceSieve<T>
+where
+    T: Unsigned,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Self::next(self)
+    }
+}
+
+/// Checks the residue class parameters and returns the class of odd numbers equal to `residue` modulo `modulus`
+/// as a pair `(residue, step)`, where `step = lcm(2, modulus)`.
+fn odd_residue_class<T: Unsigned>(residue: &T, modulus: &T, max_bit_length: u32) -> Result<(T, T), Error> {
+    if bool::from(modulus.is_zero()) || modulus.bits_vartime() >= max_bit_length {
+        return Err(Error::InvalidParameter { name: "modulus" });
+    }
+    if residue >= modulus || gcd_big_vartime(residue, modulus) != T::one_like(modulus) {
+        return Err(Error::InvalidParameter { name: "residue" });
+    }
+
+    if modulus.is_odd().into() {
+        // Combine with `1 mod 2` using the CRT.
+        let step = modulus.wrapping_shl_vartime(1);
+        let residue = if residue.is_odd().into() {
+            residue.clone()
+        } else {
+            residue.wrapping_add(modulus)
+        };
+        Ok((residue, step))
+    } else {
+        // `residue` is odd since it is coprime with `modulus`.
+        Ok((residue.clone(), modulus.clone()))
+    }
+}
+
+/// A sieve factory producing sieves over the numbers of a given bit length equal to `residue` modulo `modulus`.
+///
+/// The first sieve starts at a random point and goes until the largest number of the given bit length,
+/// and the second one wraps around, starting at the smallest number of the given bit length
+/// and stopping right before the first starting point.
+/// After that, the factory stops producing sieves.
+#[derive(Debug, Clone)]
+pub struct CongruenceSieveFactory<T> {
+    max_bit_length: NonZeroU32,
+    residue: T,
+    modulus: T,
+    start: Option<T>,
+    wrapped: bool,
+}
+
+impl<T> CongruenceSieveFactory<T>
+where
+    T: Unsigned + RandomBits,
+{
+    /// Creates a factory that produces sieves returning numbers of exactly `max_bit_length` bits
+    /// that are equal to `residue` modulo `modulus` and are not divisible by a number of small factors.
+    ///
+    /// Returns an error if `max_bit_length < 2`, if `modulus` is zero or `lcm(2, modulus)` is not smaller
+    /// than `2^(max_bit_length - 1)` (so the residue class is not guaranteed to contain numbers
+    /// of `max_bit_length` bits), or if `residue` is not smaller than and coprime with `modulus`.
+    pub fn new(max_bit_length: u32, residue: T, modulus: T) -> Result<Self, Error> {
+        if max_bit_length < 2 {
+            return Err(Error::BitLengthTooSmall {
+                bit_length: max_bit_length,
+                flavor: Flavor::Any,
+            });
+        }
+
+        let (_residue, step) = odd_residue_class(&residue, &modulus, max_bit_length)?;
+        if step.bits_vartime() >= max_bit_length {
+            return Err(Error::InvalidParameter { name: "modulus" });
+        }
+
+        let max_bit_length = NonZeroU32::new(max_bit_length).expect("`max_bit_length` should be non-zero");
+        Ok(Self {
+            max_bit_length,
+            residue,
+            modulus,
+            start: None,
+            wrapped: false,
+        })
+    }
+}
+
+impl<T> SieveFactory for CongruenceSieveFactory<T>
+where
+    T: Unsigned + RandomBits,
+{
+    type Item = T;
+    type Sieve = CongruenceSieve<T>;
+    fn make_sieve<R>(
+        &mut self,
+        rng: &mut R,
+        _previous_sieve: Option<&Self::Sieve>,
+    ) -> Result<Option<Self::Sieve>, Error>
+    where
+        R: CryptoRng + ?Sized,
+    {
+        match &self.start {
+            None => {
+                let start = random_odd_integer::<T, _>(rng, self.max_bit_length, SetBits::Msb)?.get();
+                self.start = Some(start.clone());
+                Ok(Some(CongruenceSieve::new(
+                    start,
+                    self.max_bit_length,
+                    &self.residue,
+                    &self.modulus,
+                )?))
+            }
+            Some(start) => {
+                if self.wrapped {
+                    return Ok(None);
+                }
+                self.wrapped = true;
+                // `start` is odd and has its MSB set, so it is greater than `2^(max_bit_length - 1)`.
+                let one = T::one_like(start);
+                let min = one.wrapping_shl_vartime(self.max_bit_length.get() - 1);
+                let max = start.wrapping_sub(&one);
+                Ok(Some(CongruenceSieve::new_bounded(
+                    min,
+                    self.max_bit_length,
+                    Some(&max),
+                    &self.residue,
+                    &self.modulus,
+                )?))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::num::NonZero;
+
+    use crypto_bigint::{BoxedUint, U64, U128};
+    use num_prime::nt_funcs::{factorize64, is_prime64};
+    use rand::rngs::ChaCha8Rng;
+    use rand_core::SeedableRng;
+
+    use super::{CongruenceSieve, CongruenceSieveFactory};
+    use crate::{
+        Error, Flavor,
+        hazmat::{SetBits, SieveFactory, precomputed::SMALL_PRIMES, random_odd_integer},
+    };
+
+    fn check_sieve(start: u32, bit_length: u32, residue: u32, modulus: u32) {
+        let test = CongruenceSieve::new(
+            U64::from(start),
+            NonZero::new(bit_length).unwrap(),
+            &U64::from(residue),
+            &U64::from(modulus),
+        )
+        .unwrap()
+        .map(u64::from)
+        .collect::<Vec<_>>();
+
+        // The reference: all the odd numbers from the residue class in the range,
+        // excluding the multiples of small primes smaller than the first element.
+        let mut reference = Vec::new();
+        let mut first = None;
+        for num in u64::from(start)..(1u64 << bit_length) {
+            if num % 2 == 0 || num % u64::from(modulus) != u64::from(residue) {
+                continue;
+            }
+            let first = *first.get_or_insert(num);
+            if SMALL_PRIMES
+                .iter()
+                .map(|p| u64::from(*p))
+                .take_while(|p| *p < first)
+                .any(|p| num % p == 0)
+            {
+                continue;
+            }
+            reference.push(num);
+        }
+
+        assert_eq!(test, reference);
+    }
+
+    #[test]
+    fn small_range() {
+        check_sieve(1, 6, 1, 4);
+        check_sieve(1, 6, 3, 8);
+        check_sieve(1, 8, 2, 3);
+        check_sieve(5, 8, 1, 6);
+        check_sieve(20, 10, 7, 8);
+        check_sieve(100, 12, 1, 16);
+        check_sieve(1000, 12, 4, 15);
+        check_sieve(17000, 16, 12, 35);
+    }
+
+    #[test]
+    fn empty_range() {
+        check_sieve(60, 6, 1, 16); // the next candidate is 65, which is 7 bits long
+        check_sieve(63, 6, 1, 4);
+    }
+
+    #[test]
+    fn random() {
+        let max_prime = SMALL_PRIMES[SMALL_PRIMES.len() - 1];
+
+        let mut rng = ChaCha8Rng::from_seed(*b"01234567890123456789012345678901");
+        let start = random_odd_integer::<U64, _>(&mut rng, NonZero::new(32).unwrap(), SetBits::Msb)
+            .unwrap()
+            .get();
+        for num in CongruenceSieve::new(start, NonZero::new(32).unwrap(), &U64::from(5u32), &U64::from(12u32))
+            .unwrap()
+            .take(100)
+        {
+            let num_u64 = u64::from(num);
+            assert!(num_u64.leading_zeros() == 32);
+            assert_eq!(num_u64 % 12, 5);
+
+            let factors_and_powers = factorize64(num_u64);
+            let factors = factors_and_powers.into_keys().collect::<Vec<_>>();
+            assert!(factors[0] > max_prime as u64);
+        }
+    }
+
+    #[test]
+    fn factory() {
+        let mut rng = rand::rng();
+        let modulus = U128::ONE.wrapping_shl_vartime(40);
+        let mut factory = CongruenceSieveFactory::new(100, U128::ONE, modulus).unwrap();
+        let sieve = factory.make_sieve(&mut rng, None).unwrap().unwrap();
+        for num in sieve.take(100) {
+            assert_eq!(num.bits_vartime(), 100);
+            assert_eq!(num.as_words()[0] & ((1 << 40) - 1), 1);
+        }
+
+        let mut factory = CongruenceSieveFactory::new(100, BoxedUint::from(7u32), BoxedUint::from(8u32)).unwrap();
+        let sieve = factory.make_sieve(&mut rng, None).unwrap().unwrap();
+        for num in sieve.take(100) {
+            assert_eq!(num.bits_vartime(), 100);
+            assert_eq!(num.as_words()[0] & 7, 7);
+        }
+    }
+
+    fn collect_all(factory: &mut CongruenceSieveFactory<U64>) -> Vec<u64> {
+        let mut rng = rand::rng();
+        let mut result = Vec::new();
+        let mut previous = None;
+        while let Some(sieve) = factory.make_sieve(&mut rng, previous.as_ref()).unwrap() {
+            result.extend(sieve.clone().map(u64::from));
+            previous = Some(sieve);
+        }
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn factory_wraps_around() {
+        for (bit_length, residue, modulus) in [(8u32, 1u32, 4u32), (10, 7, 8), (12, 4, 15), (16, 1, 1024)] {
+            let mut factory = CongruenceSieveFactory::new(bit_length, U64::from(residue), U64::from(modulus)).unwrap();
+            let result = collect_all(&mut factory);
+
+            let mut deduped = result.clone();
+            deduped.dedup();
+            assert_eq!(deduped, result);
+
+            let (residue, modulus) = (u64::from(residue), u64::from(modulus));
+            let min = 1u64 << (bit_length - 1);
+            let max = (1u64 << bit_length) - 1;
+            for num in result.iter() {
+                assert!((min..=max).contains(num));
+                assert!(num % 2 == 1 && num % modulus == residue);
+            }
+            for num in min..=max {
+                if num % modulus == residue && is_prime64(num) {
+                    assert!(result.contains(&num));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn factory_empty_class() {
+        // The only 6-bit numbers equal to 1 modulo 16 are 33 and 49, neither of which is prime.
+        let mut factory = CongruenceSieveFactory::new(6, U64::ONE, U64::from(16u32)).unwrap();
+        assert!(collect_all(&mut factory).iter().all(|num| *num == 33 || *num == 49));
+
+        let mut factory = CongruenceSieveFactory::new(6, U64::ONE, U64::from(16u32)).unwrap();
+        let mut rng = rand::rng();
+        assert!(factory.make_sieve(&mut rng, None).unwrap().is_some());
+        assert!(factory.make_sieve(&mut rng, None).unwrap().is_some());
+        assert!(factory.make_sieve(&mut rng, None).unwrap().is_none());
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert_eq!(
+            CongruenceSieveFactory::new(1, U64::ONE, U64::from(4u32)).unwrap_err(),
+            Error::BitLengthTooSmall {
+                bit_length: 1,
+                flavor: Flavor::Any
+            }
+        );
+        assert_eq!(
+            CongruenceSieveFactory::new(32, U64::ONE, U64::ZERO).unwrap_err(),
+            Error::InvalidParameter { name: "modulus" }
+        );
+        assert_eq!(
+            CongruenceSieveFactory::new(32, U64::ONE, U64::ONE.wrapping_shl_vartime(31)).unwrap_err(),
+            Error::InvalidParameter { name: "modulus" }
+        );
+        assert_eq!(
+            CongruenceSieveFactory::new(32, U64::ONE, U64::ONE.wrapping_shl_vartime(30).wrapping_add(&U64::ONE))
+                .unwrap_err(),
+            Error::InvalidParameter { name: "modulus" }
+        );
+        assert_eq!(
+            CongruenceSieveFactory::new(32, U64::from(8u32), U64::from(8u32)).unwrap_err(),
+            Error::InvalidParameter { name: "residue" }
+        );
+        assert_eq!(
+            CongruenceSieveFactory::new(32, U64::from(3u32), U64::from(9u32)).unwrap_err(),
+            Error::InvalidParameter { name: "residue" }
+        );
+        assert_eq!(
+            CongruenceSieve::new(U64::ONE, NonZero::new(65).unwrap(), &U64::ONE, &U64::from(4u32)).unwrap_err(),
+            Error::BitLengthTooLarge {
+                bit_length: 65,
+                bits_precision: 64
+            }
+        );
+    }
+}
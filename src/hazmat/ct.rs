@@ -0,0 +1,239 @@
+//! Constant-time primality tests.
+
+use crypto_bigint::{
+    JacobiSymbol, Limb, NonZero, Odd, U64, Uint,
+    modular::{MontyForm, MontyParams},
+    subtle::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeGreater, ConstantTimeLess},
+};
+
+/// The number of `D` candidates tried by [`lucas_test_ct`].
+///
+/// For a prime `n`, all of them being quadratic residues requires every odd prime below `2 * SELFRIDGE_CANDIDATES`
+/// to be a residue as well, which happens with probability of about $2^{-97}$.
+const SELFRIDGE_CANDIDATES: u32 = 256;
+
+/// Performs a Miller-Rabin check of `candidate` with a given base in constant time.
+///
+/// Returns a truthy [`Choice`] if `candidate` is a strong probable prime to the given base.
+///
+/// Unlike [`MillerRabin`](`super::MillerRabin`), the running time only depends on the precision of `Uint<LIMBS>`,
+/// and not on the values of `candidate` or `base`.
+/// The price is that the squaring loop is always run for the maximum possible number of iterations,
+/// making the check about twice as slow.
+///
+/// The result for `candidate == 1` is unspecified.
+pub fn miller_rabin_ct<const LIMBS: usize>(candidate: &Odd<Uint<LIMBS>>, base: &Uint<LIMBS>) -> Choice {
+    let params = MontyParams::new(*candidate);
+    let one = MontyForm::one(params);
+    let minus_one = -one;
+
+    // Find `s` and odd `d` such that `candidate - 1 == 2^s * d`.
+    let candidate_minus_one = candidate.wrapping_sub(&Uint::ONE);
+    let s = candidate_minus_one.trailing_zeros();
+    let d = candidate_minus_one.wrapping_shr(s);
+
+    let mut test = MontyForm::new(base, params).pow(&d);
+    let mut result = test.ct_eq(&one) | test.ct_eq(&minus_one);
+
+    for i in 1..Uint::<LIMBS>::BITS {
+        test = test.square();
+        result |= i.ct_lt(&s) & test.ct_eq(&minus_one);
+    }
+
+    result
+}
+
+/// Performs a strong Lucas check of `candidate` in constant time,
+/// using the base chosen by the "Method A" of Selfridge (see [`SelfridgeBase`](`super::SelfridgeBase`)).
+///
+/// Returns a truthy [`Choice`] if `candidate` is a strong Lucas probable prime.
+/// This is the same check as [`lucas_test`](`super::lucas_test`) with [`SelfridgeBase`](`super::SelfridgeBase`)
+/// and [`LucasCheck::Strong`](`super::LucasCheck::Strong`), except for the following.
+///
+/// - Instead of stopping at the first `D` with the Jacobi symbol `(D/n) == -1`,
+///   a fixed number of candidates for `D` is tested, and the first suitable one is selected in constant time.
+///   If none of them is suitable, `candidate` is reported as composite.
+///   This is always the case for squares, and happens for primes with probability of about $2^{-97}$.
+/// - The Lucas sequence is evaluated on all the bits of `Uint<LIMBS>`,
+///   and the check of `V_{d 2^r}` runs for the maximum possible number of iterations.
+///
+/// The running time only depends on the precision of `Uint<LIMBS>`, and not on the value of `candidate`.
+pub fn lucas_test_ct<const LIMBS: usize>(candidate: &Odd<Uint<LIMBS>>) -> Choice {
+    let n = candidate.as_ref();
+
+    // Select `D` from `5, -7, 9, -11, 13, ...`, such that `(D/n) == -1`.
+    let mut found = Choice::from(0);
+    let mut composite = Choice::from(0);
+    let mut abs_d = 5u32;
+    let mut d_is_negative = 0u8;
+
+    for i in 0..SELFRIDGE_CANDIDATES {
+        let candidate_abs_d = 5 + 2 * i;
+        let candidate_d_is_negative = (i & 1) as u8;
+        let abs_q = if candidate_d_is_negative == 1 {
+            (candidate_abs_d + 1) / 4
+        } else {
+            (candidate_abs_d - 1) / 4
+        };
+
+        // For the odd `m = |D|` from the Selfridge sequence, `D == 1 mod 4` if `D` is positive,
+        // and `D == 3 mod 4` if it is negative. Then by quadratic reciprocity `(D/n) == (n/m)`.
+        let j = jacobi_small(n, candidate_abs_d);
+
+        let selected = !found & Choice::from(j.is_minus_one());
+
+        // If `(D/n) == 0`, `n` is composite unless it is equal to `|D|` (see `SelfridgeBase`).
+        let is_abs_d = n.ct_eq(&Uint::from_u32(candidate_abs_d));
+        composite |= !found & Choice::from(j.is_zero()) & !is_abs_d;
+
+        // The Lucas test requires `gcd(n, Q) == 1`.
+        if abs_q > 1 {
+            let divides = rem_small(n, abs_q).ct_eq(&Limb::ZERO);
+            composite |= selected & divides & n.ct_gt(&Uint::from_u32(abs_q));
+        }
+
+        abs_d.conditional_assign(&candidate_abs_d, selected);
+        d_is_negative.conditional_assign(&candidate_d_is_negative, selected);
+        found |= selected;
+    }
+
+    let d_is_negative = Choice::from(d_is_negative);
+
+    // Calculate `Q = (1 - D) / 4`.
+    let abs_q = u32::conditional_select(&((abs_d - 1) / 4), &((abs_d + 1) / 4), d_is_negative);
+
+    let params = MontyParams::new(*candidate);
+    let zero = MontyForm::zero(params);
+    let one = MontyForm::one(params);
+    let two = one + one;
+
+    let abs_d = MontyForm::new(&Uint::from_u32(abs_d), params);
+    let d_m = MontyForm::conditional_select(&abs_d, &-abs_d, d_is_negative);
+    let abs_q = MontyForm::new(&Uint::from_u32(abs_q), params);
+    let q = MontyForm::conditional_select(&-abs_q, &abs_q, d_is_negative);
+
+    // Find `d` and `s`, such that `d` is odd and `d * 2^s = n + 1`.
+    let n_plus_one = n.wrapping_add(&Uint::ONE);
+    let s = n_plus_one.trailing_zeros();
+    let d = n_plus_one.wrapping_shr(s);
+
+    // Compute the `d`-th element of the Lucas sequence with `P = 1` (see `lucas_test()` for the formulas),
+    // going through all the bits of `Uint<LIMBS>`.
+    // While `k == 0`, doubling it leaves `U_0 = 0`, `V_0 = 2`, `Q^0 = 1` unchanged.
+    let mut uk = zero;
+    let mut vk = two;
+    let mut qk = one;
+
+    for i in (0..Uint::<LIMBS>::BITS).rev() {
+        // k' = 2k
+        uk *= vk;
+        vk = vk.square() - qk - qk;
+        qk = qk.square();
+
+        // k' = k + 1
+        let uk_next = (uk + vk).div_by_2();
+        let vk_next = (d_m * uk + vk).div_by_2();
+        let qk_next = qk * q;
+
+        let bit = Choice::from(d.bit(i));
+        uk.conditional_assign(&uk_next, bit);
+        vk.conditional_assign(&vk_next, bit);
+        qk.conditional_assign(&qk_next, bit);
+    }
+
+    // Check if `U_d == 0` or `V_{d 2^r} == 0` for some `0 <= r < s`.
+    let mut result = uk.ct_eq(&zero) | vk.ct_eq(&zero);
+    for r in 1..Uint::<LIMBS>::BITS {
+        vk = vk.square() - qk - qk;
+        qk = qk.square();
+        result |= r.ct_lt(&s) & vk.ct_eq(&zero);
+    }
+
+    found & !composite & result
+}
+
+/// Returns `n mod m` for a small public `m`, in constant time with respect to `n`.
+fn rem_small<const LIMBS: usize>(n: &Uint<LIMBS>, m: u32) -> Limb {
+    n.rem_limb(NonZero::new(Limb::from(m)).expect("the divisor is non-zero"))
+}
+
+/// Returns the Jacobi symbol `(n/m)` for a small odd public `m`, in constant time with respect to `n`.
+fn jacobi_small<const LIMBS: usize>(n: &Uint<LIMBS>, m: u32) -> JacobiSymbol {
+    let rem = U64::from(rem_small(n, m));
+    rem.jacobi_symbol(&Odd::new(U64::from_u32(m)).expect("the modulus is odd"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto_bigint::{Odd, U64, U128, U1024, Uint};
+    use num_prime::nt_funcs::is_prime64;
+
+    use super::{lucas_test_ct, miller_rabin_ct};
+    use crate::hazmat::{
+        LucasCheck, MillerRabin, SelfridgeBase, lucas_test, primes,
+        pseudoprimes::{STRONG_BASE_2, STRONG_LUCAS},
+    };
+
+    fn odd<const LIMBS: usize>(x: u32) -> Odd<Uint<LIMBS>> {
+        Odd::new(Uint::from_u32(x)).unwrap()
+    }
+
+    #[test]
+    fn miller_rabin_matches_vartime() {
+        for x in (3..10000u32).step_by(2) {
+            let candidate = odd::<{ U64::LIMBS }>(x);
+            let expected = MillerRabin::new(candidate).test_base_two().is_probably_prime();
+            let result: bool = miller_rabin_ct(&candidate, &U64::from(2u32)).into();
+            assert_eq!(result, expected, "x = {x}");
+        }
+    }
+
+    #[test]
+    fn miller_rabin_pseudoprimes() {
+        for x in STRONG_BASE_2.iter() {
+            assert!(bool::from(miller_rabin_ct(
+                &odd::<{ U64::LIMBS }>(*x),
+                &U64::from(2u32)
+            )));
+        }
+    }
+
+    #[test]
+    fn lucas_matches_vartime() {
+        for x in (3..10000u32).step_by(2) {
+            let candidate = odd::<{ U64::LIMBS }>(x);
+            let expected = lucas_test(candidate, SelfridgeBase, LucasCheck::Strong).is_probably_prime();
+            let result: bool = lucas_test_ct(&candidate).into();
+            assert_eq!(result, expected, "x = {x}");
+        }
+    }
+
+    #[test]
+    fn lucas_pseudoprimes() {
+        for x in STRONG_LUCAS.iter() {
+            assert!(!is_prime64(*x as u64));
+            assert!(bool::from(lucas_test_ct(&odd::<{ U64::LIMBS }>(*x))));
+        }
+    }
+
+    #[test]
+    fn squares() {
+        for x in (3..1000u32).step_by(2) {
+            assert!(!bool::from(lucas_test_ct(&odd::<{ U64::LIMBS }>(x * x))));
+        }
+    }
+
+    #[test]
+    fn large_primes() {
+        for p in primes::PRIMES_128.iter() {
+            let p = Odd::new(*p).unwrap();
+            assert!(bool::from(miller_rabin_ct(&p, &U128::from(2u32))));
+            assert!(bool::from(lucas_test_ct(&p)));
+        }
+        for p in primes::PRIMES_1024.iter() {
+            let p = Odd::new(*p).unwrap();
+            assert!(bool::from(miller_rabin_ct(&p, &U1024::from(2u32))));
+            assert!(bool::from(lucas_test_ct(&p)));
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! An iterator over the possible starting points of Cunningham chains,
+//! weeding out the numbers for which any element of the chain is a multiple of a small prime.
+
+use core::{marker::PhantomData, num::NonZeroU32};
+
+use crypto_bigint::{RandomBits, Unsigned};
+use rand_core::CryptoRng;
+
+use super::sieve::{ProgressionSieve, SetBits, SieveFactory, random_odd_integer, zero_with_bit_length};
+use crate::{
+    error::Error,
+    presets::{CunninghamKind, Flavor},
+};
+
+/// An iterator returning odd numbers `p` with up to and including given bit length, starting from a given number,
+/// such that none of the first `length` elements of the Cunningham chain of the given kind starting from `p`
+/// are multiples of the first 2048 small primes.
+///
+/// The elements of the chain are `p_{i+1} = 2 p_i + 1` for [`CunninghamKind::First`],
+/// and `p_{i+1} = 2 p_i - 1` for [`CunninghamKind::Second`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CunninghamSieve<T: Unsigned> {
+    progression: ProgressionSieve<T>,
+    kind: CunninghamKind,
+    length: usize,
+}
+
+impl<T> CunninghamSieve<T>
+where
+    T: Unsigned,
+{
+    /// Creates a new sieve, iterating from `start` and until the last number with `max_bit_length` bits,
+    /// producing odd numbers `p` such that the first `length` elements of the Cunningham chain
+    /// of the given `kind` starting from `p` are not multiples of a list of small primes in the range `[2, start)`.
+    ///
+    /// Note that the sieve does not check that the elements of the chain fit in the precision of `T`.
+    ///
+    /// Returns an error if `max_bit_length` is greater than the precision of `start`, or if `length` is zero.
+    pub fn new(start: T, max_bit_length: NonZeroU32, kind: CunninghamKind, length: usize) -> Result<Self, Error> {
+        let max_bit_length = max_bit_length.get();
+
+        if max_bit_length > start.bits_precision() {
+            return Err(Error::BitLengthTooLarge {
+                bit_length: max_bit_length,
+                bits_precision: start.bits_precision(),
+            });
+        }
+        if length == 0 {
+            return Err(Error::InvalidParameter { name: "length" });
+        }
+
+        // Adjust the start so that we hit odd numbers when incrementing it by 2.
+        let start = if start < T::from(3u32) {
+            T::from(3u32)
+        } else {
+            start | T::one()
+        };
+
+        // `2^max_bit_length - 1`, calculated in a way that does not overflow.
+        let half = T::one_like(&start).wrapping_shl_vartime(max_bit_length - 1);
+        let max_value = half.wrapping_sub(&T::one_like(&start)).wrapping_add(&half);
+
+        // All the other elements of the chain are greater than the first one,
+        // so the small primes used for sieving `p` can be used for them too.
+        let step = T::from_limb_like(2u32.into(), &start);
+        Ok(Self {
+            progression: ProgressionSieve::increasing(start, max_value, step),
+            kind,
+            length,
+        })
+    }
+
+    fn next(&mut self) -> Option<T> {
+        let kind = self.kind;
+        let length = self.length;
+        self.progression.next_by(|mut r, d| {
+            // An extension of the trick from "Safe Prime Generation with a Combined Sieve" by Michael J. Wiener
+            // (https://eprint.iacr.org/2003/186): knowing `p mod d`, we can get the residues
+            // of all the other elements of the chain for virtually no additional cost.
+            for _ in 0..length {
+                if r == 0 {
+                    return true;
+                }
+                r = match kind {
+                    CunninghamKind::First => (2 * r + 1) % d,
+                    CunninghamKind::Second => (2 * r + d - 1) % d,
+                };
+            }
+            false
+        })
+    }
+}
+
+impl<T> Iterator for CunninghamSieve<T>
+where
+    T: Unsigned,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Self::next(self)
+    }
+}
+
+/// A sieve factory producing sieves over the possible starting points of Cunningham chains.
+#[derive(Debug, Clone, Copy)]
+pub struct CunninghamSieveFactory<T> {
+    max_bit_length: NonZeroU32,
+    chain_bit_length: u32,
+    kind: CunninghamKind,
+    length: usize,
+    phantom: PhantomData<T>,
+}
+
+impl<T> CunninghamSieveFactory<T>
+where
+    T: Unsigned + RandomBits,
+{
+    /// Creates a factory that produces sieves returning numbers of exactly `max_bit_length` bits
+    /// that can start a Cunningham chain of the given `kind` and `length`
+    /// (that is, the elements of the chain are not divisible by a number of small factors).
+    ///
+    /// Returns an error if `max_bit_length < 2`, if `length` is zero,
+    /// or if the last element of the chain may not fit in the precision of `T` (for fixed-size integers).
+    pub fn new(kind: CunninghamKind, length: usize, max_bit_length: u32) -> Result<Self, Error> {
+        if max_bit_length < 2 {
+            return Err(Error::BitLengthTooSmall {
+                bit_length: max_bit_length,
+                flavor: Flavor::Any,
+            });
+        }
+        if length == 0 {
+            return Err(Error::InvalidParameter { name: "length" });
+        }
+
+        // The last element of the chain is less than `2^(length - 1) * (p + 1)`,
+        // so it has at most `max_bit_length + length - 1` bits.
+        let chain_bit_length = u32::try_from(length - 1)
+            .ok()
+            .and_then(|length| max_bit_length.checked_add(length))
+            .ok_or(Error::InvalidParameter { name: "length" })?;
+        zero_with_bit_length::<T>(chain_bit_length)?;
+
+        let max_bit_length = NonZeroU32::new(max_bit_length).expect("`max_bit_length` should be non-zero");
+        Ok(Self {
+            max_bit_length,
+            chain_bit_length,
+            kind,
+            length,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<T> SieveFactory for CunninghamSieveFactory<T>
+where
+    T: Unsigned + RandomBits,
+{
+    type Item = T;
+    type Sieve = CunninghamSieve<T>;
+    fn make_sieve<R>(
+        &mut self,
+        rng: &mut R,
+        _previous_sieve: Option<&Self::Sieve>,
+    ) -> Result<Option<Self::Sieve>, Error>
+    where
+        R: CryptoRng + ?Sized,
+    {
+        let start = random_odd_integer::<T, _>(rng, self.max_bit_length, SetBits::Msb)?;
+        // Make sure the whole chain fits in the precision of the returned numbers
+        // (only matters for boxed integers).
+        let start = zero_with_bit_length::<T>(self.chain_bit_length)?.wrapping_add(start.as_ref());
+        Ok(Some(CunninghamSieve::new(
+            start,
+            self.max_bit_length,
+            self.kind,
+            self.length,
+        )?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::num::NonZero;
+
+    use crypto_bigint::{U64, U128};
+
+    use super::{CunninghamSieve, CunninghamSieveFactory};
+    use crate::{CunninghamKind, Error, Flavor, hazmat::precomputed::SMALL_PRIMES};
+
+    fn check_sieve(start: u32, bit_length: u32, kind: CunninghamKind, length: usize) {
+        let test = CunninghamSieve::new(U64::from(start), NonZero::new(bit_length).unwrap(), kind, length)
+            .unwrap()
+            .map(u64::from)
+            .collect::<Vec<_>>();
+
+        // The reference: all the odd numbers in the range for which no element of the chain
+        // is a multiple of a small prime smaller than the first odd number in the range.
+        let first = core::cmp::max(u64::from(start) | 1, 3);
+        let mut reference = Vec::new();
+        for num in (first..(1u64 << bit_length)).step_by(2) {
+            let mut element = num;
+            let mut composite = false;
+            for _ in 0..length {
+                if SMALL_PRIMES
+                    .iter()
+                    .map(|p| u64::from(*p))
+                    .take_while(|p| *p < first)
+                    .any(|p| element % p == 0)
+                {
+                    composite = true;
+                    break;
+                }
+                element = match kind {
+                    CunninghamKind::First => 2 * element + 1,
+                    CunninghamKind::Second => 2 * element - 1,
+                };
+            }
+            if !composite {
+                reference.push(num);
+            }
+        }
+
+        assert_eq!(test, reference);
+    }
+
+    #[test]
+    fn small_range() {
+        for kind in [CunninghamKind::First, CunninghamKind::Second] {
+            for length in 1..5 {
+                check_sieve(1, 10, kind, length);
+                check_sieve(3, 10, kind, length);
+                check_sieve(30, 12, kind, length);
+                check_sieve(1023, 10, kind, length);
+                check_sieve(4000, 16, kind, length);
+            }
+        }
+    }
+
+    #[test]
+    fn empty_range() {
+        let sieve = CunninghamSieve::new(U64::from(1024u32), NonZero::new(10).unwrap(), CunninghamKind::First, 2);
+        assert_eq!(sieve.unwrap().next(), None);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            CunninghamSieve::new(U64::ONE, NonZero::new(65).unwrap(), CunninghamKind::First, 2).unwrap_err(),
+            Error::BitLengthTooLarge {
+                bit_length: 65,
+                bits_precision: 64
+            }
+        );
+        assert_eq!(
+            CunninghamSieve::new(U64::ONE, NonZero::new(10).unwrap(), CunninghamKind::First, 0).unwrap_err(),
+            Error::InvalidParameter { name: "length" }
+        );
+
+        assert_eq!(
+            CunninghamSieveFactory::<U128>::new(CunninghamKind::First, 2, 1).unwrap_err(),
+            Error::BitLengthTooSmall {
+                bit_length: 1,
+                flavor: Flavor::Any
+            }
+        );
+        assert_eq!(
+            CunninghamSieveFactory::<U128>::new(CunninghamKind::Second, 0, 64).unwrap_err(),
+            Error::InvalidParameter { name: "length" }
+        );
+        assert_eq!(
+            CunninghamSieveFactory::<U128>::new(CunninghamKind::Second, 5, 125).unwrap_err(),
+            Error::BitLengthTooLarge {
+                bit_length: 129,
+                bits_precision: 128
+            }
+        );
+        assert!(CunninghamSieveFactory::<U128>::new(CunninghamKind::Second, 5, 124).is_ok());
+    }
+}
@@ -0,0 +1,206 @@
+//! A segmented sieve of Eratosthenes for enumerating the primes that fit in `u64`.
+
+use alloc::{vec, vec::Vec};
+
+// The number of odd numbers covered by a single segment.
+// The segment is stored as a vector of `bool`, so it takes 32 KiB.
+const SEGMENT_LEN: u64 = 1 << 15;
+
+/// An iterator over all the primes `<= max` in increasing order, for `max` fitting in `u64`.
+///
+/// The numbers are sieved in segments of a fixed size, so the memory taken by the segment is bounded,
+/// and the only memory that grows is the list of the sieving primes, which are the primes up to `sqrt(max)`.
+/// Since the primes are produced lazily, stopping the iteration early only requires the primes
+/// up to the square root of the last produced one.
+#[derive(Clone, Debug)]
+pub struct SegmentedSieve {
+    max: u64,
+    // The segment covers the odd numbers `low + 2 * i + 1` that are not greater than `max`,
+    // and `segment[i]` is `true` if the number is composite.
+    low: u64,
+    segment: Vec<bool>,
+    position: usize,
+    // The primes not greater than `sqrt(max)`, used to sieve the segments.
+    sieving_primes: Vec<u32>,
+    two_produced: bool,
+    exhausted: bool,
+}
+
+impl SegmentedSieve {
+    /// Creates a new iterator over all the primes `<= max`.
+    pub fn new(max: u64) -> Self {
+        let mut sieve = Self {
+            max,
+            low: 0,
+            segment: vec![false; SEGMENT_LEN as usize],
+            position: 0,
+            sieving_primes: Vec::new(),
+            two_produced: max < 2,
+            exhausted: max < 3,
+        };
+        if !sieve.exhausted {
+            sieve.sieve_first_segment();
+        }
+        sieve
+    }
+
+    // The last number covered by the current segment.
+    fn segment_last(&self) -> u64 {
+        self.low.saturating_add(2 * SEGMENT_LEN - 1).min(self.max)
+    }
+
+    // The first segment contains its own sieving primes, so it is sieved in the classic way.
+    fn sieve_first_segment(&mut self) {
+        let last = self.segment_last();
+        self.segment.fill(false);
+        // 1 is not a prime
+        self.segment[0] = true;
+        let mut i = 1;
+        loop {
+            let p = 2 * i as u64 + 1;
+            if p * p > last {
+                break;
+            }
+            if !self.segment[i] {
+                let mut j = ((p * p - 1) / 2) as usize;
+                while j < self.segment.len() {
+                    self.segment[j] = true;
+                    j += p as usize;
+                }
+            }
+            i += 1;
+        }
+    }
+
+    // Moves to the next segment and sieves it with the primes found so far.
+    fn next_segment(&mut self) -> bool {
+        self.low = match self.low.checked_add(2 * SEGMENT_LEN) {
+            Some(low) if low < self.max => low,
+            _ => return false,
+        };
+        self.position = 0;
+        self.segment.fill(false);
+
+        let last = self.segment_last();
+        for p in self.sieving_primes.iter().map(|p| u64::from(*p)) {
+            if p * p > last {
+                break;
+            }
+            // The first odd multiple of `p` in the segment.
+            let first = (self.low / p + 1) * p;
+            let first = if first % 2 == 0 { first + p } else { first };
+            let mut j = (first - self.low - 1) / 2;
+            while j < SEGMENT_LEN {
+                self.segment[j as usize] = true;
+                j += p;
+            }
+        }
+        true
+    }
+
+    fn next(&mut self) -> Option<u64> {
+        if !self.two_produced {
+            self.two_produced = true;
+            return Some(2);
+        }
+
+        while !self.exhausted {
+            let last = self.segment_last();
+            while self.position < self.segment.len() {
+                let num = self.low + 2 * self.position as u64 + 1;
+                if num > last {
+                    break;
+                }
+                let is_composite = self.segment[self.position];
+                self.position += 1;
+                if !is_composite {
+                    // Keep the primes that are needed for sieving the following segments.
+                    if num.checked_mul(num).is_some_and(|square| square <= self.max) {
+                        self.sieving_primes
+                            .push(u32::try_from(num).expect("the square of `num` fits in `u64`"));
+                    }
+                    return Some(num);
+                }
+            }
+            if !self.next_segment() {
+                self.exhausted = true;
+            }
+        }
+        None
+    }
+}
+
+impl Iterator for SegmentedSieve {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Self::next(self)
+    }
+}
+
+/// Returns the first `count` odd primes (that is, starting from 3).
+///
+/// This can be used to build a table of small primes larger than the one the crate uses internally.
+pub fn small_odd_primes(count: usize) -> Vec<u32> {
+    SegmentedSieve::new(u64::from(u32::MAX))
+        .skip(1)
+        .take(count)
+        .map(|p| u32::try_from(p).expect("the primes are bounded by `u32::MAX`"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use num_prime::nt_funcs::is_prime64;
+
+    use super::{SEGMENT_LEN, SegmentedSieve, small_odd_primes};
+    use crate::hazmat::precomputed::SMALL_PRIMES;
+
+    fn check_sieve(max: u64) {
+        let primes = SegmentedSieve::new(max).collect::<Vec<_>>();
+        let reference = (0..=max).filter(|x| is_prime64(*x)).collect::<Vec<_>>();
+        assert_eq!(primes, reference);
+    }
+
+    #[test]
+    fn small_bounds() {
+        for max in 0..100 {
+            check_sieve(max);
+        }
+    }
+
+    #[test]
+    fn segment_boundaries() {
+        for max in [
+            2 * SEGMENT_LEN - 1,
+            2 * SEGMENT_LEN,
+            2 * SEGMENT_LEN + 1,
+            4 * SEGMENT_LEN + 3,
+            1_000_000,
+        ] {
+            check_sieve(max);
+        }
+    }
+
+    #[test]
+    fn prime_count() {
+        // pi(10^7) = 664579
+        assert_eq!(SegmentedSieve::new(10_000_000).count(), 664579);
+    }
+
+    #[test]
+    fn large_bound() {
+        // Stopping early only takes a few segments, even for the largest bound.
+        let primes = SegmentedSieve::new(u64::MAX).take(100_000).collect::<Vec<_>>();
+        assert_eq!(primes.last(), Some(&1299709));
+    }
+
+    #[test]
+    fn small_primes_table() {
+        let table = small_odd_primes(SMALL_PRIMES.len() + 1000);
+        assert!(SMALL_PRIMES.iter().zip(table.iter()).all(|(x, y)| u32::from(*x) == *y));
+        assert!(table.iter().all(|p| is_prime64(u64::from(*p))));
+    }
+}
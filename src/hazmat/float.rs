@@ -1,7 +1,7 @@
 //! Const-context floating point functions that are currently not present in `core`.
 
 use core::f64;
-use crypto_bigint::Uint;
+use crypto_bigint::{Limb, Unsigned};
 
 /// Calculates `base^exp`.
 const fn pow(mut base: f64, mut exp: u32) -> f64 {
@@ -112,48 +112,33 @@ pub(crate) const fn floor_sqrt(x: u32) -> u32 {
     (low + high) / 2
 }
 
+/// Returns the lowest 128 bits of `x`.
+pub(crate) fn low_u128<T: Unsigned>(x: &T) -> u128 {
+    x.as_ref()
+        .iter()
+        .take((u128::BITS / Limb::BITS) as usize)
+        .enumerate()
+        .fold(0, |acc, (i, limb)| {
+            acc | (u128::from(limb.0) << (i as u32 * Limb::BITS))
+        })
+}
+
 // Calculate the natural logarithm of a big integer using the relation ln(x) = log₂(x) / log₂(e).
 // Uses fixed-point arithmetic for large values of x (> 2^53).
-pub(crate) fn ln<const LIMBS: usize>(x: &Uint<LIMBS>) -> f64 {
-    if x <= &Uint::ONE {
+pub(crate) fn ln<T: Unsigned>(x: &T) -> f64 {
+    if x.bits_vartime() <= 1 {
         return 0.0;
     }
-    let ilog2_x = x.bits_vartime().saturating_sub(1);
+    let ilog2_x = x.bits_vartime() - 1;
     // if x is small enough to be cast losslessly to an f64 we use the normal 64-bit log2() from `libm`.
     if ilog2_x < f64::MANTISSA_DIGITS {
-        let x = {
-            #[cfg(target_pointer_width = "64")]
-            {
-                x.as_limbs()[0].0
-            }
-            #[cfg(target_pointer_width = "32")]
-            {
-                // Small enough to fit in 32 bits
-                if ilog2_x < 32 {
-                    x.as_limbs()[0].0 as u64
-                } else {
-                    let lo = x.as_limbs()[0].0;
-                    let hi = x.as_limbs()[1].0;
-                    (hi as u64) << 32 | lo as u64
-                }
-            }
-        };
-        return libm::log2(x as f64) * f64::consts::LN_2;
+        return libm::log2(low_u128(x) as f64) * f64::consts::LN_2;
     }
     // x can be approximated by M*2^shift, where shift is `ilog2(x) - 52` and M is the integer value represented by the
     // top 53 bits of x.
     // log2(x) ~ log2(M*2^shift) ~ log2(M) + shift ~ log2(M) + ilog2(x) - 52
-    let shift = ilog2_x.saturating_sub(f64::MANTISSA_DIGITS - 1);
-    let shifted_x = x.wrapping_shr_vartime(shift);
-    #[cfg(target_pointer_width = "64")]
-    let fraction = shifted_x.as_limbs()[0].0 as f64;
-    #[cfg(target_pointer_width = "32")]
-    let fraction = {
-        let lo = shifted_x.as_limbs()[0].0;
-        let hi = shifted_x.as_limbs()[1].0;
-        let fraction = (hi as u64) << 32 | lo as u64;
-        fraction as f64
-    };
+    let shift = ilog2_x - (f64::MANTISSA_DIGITS - 1);
+    let fraction = low_u128(&x.wrapping_shr_vartime(shift)) as f64;
 
     // Fraction is now m * 2^52, where m is the top 53 bits of x. Take log2(m) and subtract 52 to scale the result back
     // to the expected range.
@@ -167,7 +152,7 @@ mod tests {
     use alloc::vec;
     use alloc::vec::Vec;
 
-    use crypto_bigint::{U128, U256, U512, U1024};
+    use crypto_bigint::{BoxedUint, U128, U256, U512, U1024};
     use float_cmp::assert_approx_eq;
     use proptest::prelude::*;
 
@@ -305,5 +290,8 @@ mod tests {
             (ln_x - expected_1024).abs() < f64::EPSILON,
             "x: {x}, ln x: {ln_x}, expected: {expected_1024}"
         );
+
+        let x_boxed = BoxedUint::from(x);
+        assert_eq!(ln(&x_boxed), ln_x);
     }
 }
@@ -31,6 +31,22 @@ where
     binary_gcd(a, b)
 }
 
+/// Calculates the greatest common divisor of `n` and `m` using the Euclidean algorithm.
+/// By definition, `gcd(n, 0) == n` and `gcd(0, m) == m`.
+pub(crate) fn gcd_big_vartime<T>(n: &T, m: &T) -> T
+where
+    T: Unsigned,
+{
+    let mut a = n.clone();
+    let mut b = m.clone();
+    while let Some(b_nonzero) = CTNonZero::new(b.clone()).into_option() {
+        let r = a % b_nonzero;
+        a = b;
+        b = r;
+    }
+    a
+}
+
 // Binary GCD lifted verbatim from [1], minus the base checks.
 // The identities mentioned in the comments are the following:
 // 1. `gcd(n, 0) = n`: everything divides 0 and n is the largest number that divides n.
@@ -80,7 +96,7 @@ mod tests {
     use num_integer::Integer;
     use proptest::prelude::*;
 
-    use super::gcd_vartime;
+    use super::{gcd_big_vartime, gcd_vartime};
 
     #[test]
     fn corner_cases() {
@@ -93,6 +109,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn corner_cases_big() {
+        assert_eq!(gcd_big_vartime(&U128::from(0u64), &U128::from(5u64)), U128::from(5u64));
+        assert_eq!(gcd_big_vartime(&U128::from(5u64), &U128::from(0u64)), U128::from(5u64));
+        assert_eq!(
+            gcd_big_vartime(&U128::from(7u64 * 11 * 13), &U128::from(11u64 * 13 * 19)),
+            U128::from(11u64 * 13)
+        );
+    }
+
     prop_compose! {
         fn uint()(bytes in any::<[u8; 16]>()) -> U128 {
             U128::from_le_slice(&bytes) | U128::ONE
@@ -113,5 +139,15 @@ mod tests {
             let gcd_test = gcd_vartime(&n, NonZero::new(m).unwrap());
             assert_eq!(gcd_test, gcd_ref);
         }
+
+        #[test]
+        fn fuzzy_big(m in uint(), n in uint()) {
+            let m_bi = BigUint::from_bytes_be(m.to_be_bytes().as_ref());
+            let n_bi = BigUint::from_bytes_be(n.to_be_bytes().as_ref());
+            let gcd_ref = n_bi.gcd(&m_bi);
+
+            let gcd_test = gcd_big_vartime(&n, &m);
+            assert_eq!(BigUint::from_bytes_be(gcd_test.to_be_bytes().as_ref()), gcd_ref);
+        }
     }
 }
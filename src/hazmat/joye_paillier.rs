@@ -0,0 +1,336 @@
+//! A sieve-free generator of candidates coprime with a product of small primes,
+//! suitable for use with constant-time primality tests.
+
+use crypto_bigint::{
+    Monty, NonZero as CTNonZero, Odd, PowBoundedExp, RandomBits, RandomMod, Unsigned, modular::Retrieve,
+};
+use rand_core::CryptoRng;
+
+use super::{
+    precomputed::SMALL_PRIMES,
+    sieve::{SieveFactory, zero_with_bit_length},
+};
+use crate::{error::Error, presets::Flavor};
+
+/// An iterator returning the candidates `b_i + t * m`, where `m = 2 * m'` is a product of small primes,
+/// `t` is fixed, and `b_i` is the odd number below `m` equal to `2^i * k` modulo `m'`, for a unit `k` modulo `m'`.
+///
+/// All the candidates are coprime with `m` by construction, and they are produced
+/// without any branching on their values.
+/// See [`JoyePaillierFactory`] for details.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JoyePaillierSieve<T: Unsigned> {
+    unit: T,
+    offset: T,
+    odd_modulus: CTNonZero<T>,
+    remaining: u32,
+}
+
+impl<T> Iterator for JoyePaillierSieve<T>
+where
+    T: Unsigned,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        // Combine the unit modulo `m'` with `1 mod 2` using the CRT:
+        // since `m'` is odd, exactly one of `k` and `k + m'` is odd.
+        let shifted = self.unit.wrapping_add(self.odd_modulus.as_ref());
+        let residue = T::ct_select(&self.unit, &shifted, self.unit.is_even());
+        let candidate = residue.wrapping_add(&self.offset);
+
+        // Step to the next unit by multiplying by 2.
+        self.unit = self.unit.add_mod(&self.unit, &self.odd_modulus);
+
+        Some(candidate)
+    }
+}
+
+/// A sieve factory implementing the prime generation method by Joye and Paillier[^JP].
+///
+/// Let `m = 2 * m'` be the product of the small primes (including 2) that fits in half of `bit_length`.
+/// For every sieve, the factory picks a random unit `k` modulo `m'`, and a random `t`
+/// such that `b + t * m` has exactly `bit_length` bits for any `b < m`.
+/// The sieve then produces the candidates `b + t * m`, where `b` is the odd number equal to `k` modulo `m'`,
+/// multiplying `k` by 2 modulo `m'` to step to the next unit.
+///
+/// Since the candidates are coprime with `m` by construction, they do not have to be sieved,
+/// and neither the unit generation nor the stepping branch on the secret values.
+/// Combined with a constant-time primality test (see [`is_prime_ct`](`crate::is_prime_ct`)),
+/// the only thing revealed by the timing is the number of candidates tested.
+///
+/// Every sieve produces at most `bit_length` candidates, after which a fresh unit and `t` are picked.
+/// This is an order of magnitude more than the expected number of candidates to be tested before a prime is found
+/// (about 15 times more for 1024-bit candidates).
+/// For small `bit_length`, the multiplicative order of 2 modulo `m'` may be smaller than that
+/// (e.g. it is 12 for `m' = 3 * 5 * 7`), and then the sieve stops after that many candidates,
+/// since the units would start repeating.
+///
+/// Only produces odd numbers (so the prime 2 is never returned).
+/// Unlike [`FouqueTibouchiFactory`](`super::FouqueTibouchiFactory`),
+/// the resulting distribution of primes is not close to uniform.
+///
+/// [^JP]: M. Joye, P. Paillier, "Fast Generation of Prime Numbers on Portable Devices: An Update",
+///   CHES 2006, LNCS 4249, 160-173 (2006),
+///   DOI: [10.1007/11894063_13](https://doi.org/10.1007/11894063_13)
+#[derive(Debug, Clone)]
+pub struct JoyePaillierFactory<T: Unsigned> {
+    // The number of candidates produced by every sieve.
+    sieve_length: u32,
+    odd_modulus: CTNonZero<T>,
+    modulus: T,
+    params: <<T as Unsigned>::Monty as Monty>::Params,
+    // The product of `p - 1` for all the primes `p` dividing `m'`, which is a multiple of its Carmichael function.
+    exponent: T,
+    t_min: T,
+    t_count: CTNonZero<T>,
+}
+
+impl<T> JoyePaillierFactory<T>
+where
+    T: Unsigned + RandomBits + RandomMod,
+{
+    /// Creates a factory that produces candidates of exactly `bit_length` bits.
+    ///
+    /// Returns an error if `bit_length < 6` (the modulus must include at least one odd prime),
+    /// or if `bit_length` is larger than the precision of `T` (for fixed-size integers).
+    pub fn new(bit_length: u32) -> Result<Self, Error> {
+        if bit_length < 6 {
+            return Err(Error::BitLengthTooSmall {
+                bit_length,
+                flavor: Flavor::Any,
+            });
+        }
+
+        let zero = zero_with_bit_length::<T>(bit_length)?;
+        let one = T::one_like(&zero);
+        let mut odd_modulus = one.clone();
+        let mut exponent = one.clone();
+        // The multiplicative order of 2 modulo `m'` (the LCM of its orders modulo the prime factors),
+        // capped at `bit_length`, since we never need more candidates than that.
+        let mut sieve_length = 1;
+        for prime in SMALL_PRIMES {
+            let new_odd_modulus = odd_modulus.wrapping_mul(&T::from(prime));
+            if new_odd_modulus.bits_vartime() + 1 > bit_length / 2 {
+                break;
+            }
+            odd_modulus = new_odd_modulus;
+            exponent = exponent.wrapping_mul(&T::from(prime - 1));
+            sieve_length = lcm(sieve_length, order_of_two(prime.into())).min(u64::from(bit_length));
+        }
+        let modulus = odd_modulus.wrapping_shl_vartime(1);
+
+        // `t` is in `[ceil(2^(bit_length - 1) / m), floor((2^bit_length - 1) / m))`,
+        // so that `b + t * m` has exactly `bit_length` bits for any `0 <= b < m`.
+        let modulus_nz = CTNonZero::new(modulus.clone()).expect("the modulus is non-zero");
+        let half = one.wrapping_shl_vartime(bit_length - 1);
+        let max_value = half.wrapping_sub(&one).wrapping_add(&half);
+        let t_min = half.wrapping_add(&modulus.wrapping_sub(&one)) / &modulus_nz;
+        let t_max = max_value / &modulus_nz;
+        let t_count = CTNonZero::new(t_max.wrapping_sub(&t_min))
+            .expect("the range of `t` is non-empty since `m <= 2^(bit_length / 2)`");
+
+        let odd_modulus = Odd::new(odd_modulus).expect("the modulus is odd by construction");
+        let params = <T as Unsigned>::Monty::new_params_vartime(odd_modulus.clone());
+
+        Ok(Self {
+            sieve_length: sieve_length
+                .try_into()
+                .expect("the sieve length is capped at `bit_length`"),
+            odd_modulus: CTNonZero::new(odd_modulus.get()).expect("the modulus is non-zero"),
+            modulus,
+            params,
+            exponent,
+            t_min,
+            t_count,
+        })
+    }
+
+    /// Picks a random unit modulo `m'` using the method from the paper:
+    /// starting from a random `k`, the components of `k` that are zero modulo some prime factor of `m'`
+    /// are replaced by random ones, without identifying the specific primes.
+    fn pick_unit<R: CryptoRng + ?Sized>(&self, rng: &mut R) -> T {
+        let one = <T as Unsigned>::Monty::one(self.params.clone());
+        let exponent_bits = self.exponent.bits_vartime();
+        let mut unit = T::random_mod(rng, &self.odd_modulus);
+        loop {
+            // `u = 1 - k^exponent` is zero modulo the primes for which `k` is a unit, and one modulo the rest.
+            let power = <T as Unsigned>::Monty::new(unit.clone(), self.params.clone())
+                .pow_bounded_exp(&self.exponent, exponent_bits);
+            let u = (one.clone() - power).retrieve();
+            if bool::from(u.is_zero()) {
+                return unit;
+            }
+            let r = T::random_mod(rng, &self.odd_modulus);
+            unit = unit.add_mod(&r.mul_mod(&u, &self.odd_modulus), &self.odd_modulus);
+        }
+    }
+}
+
+impl<T> SieveFactory for JoyePaillierFactory<T>
+where
+    T: Unsigned + RandomBits + RandomMod,
+{
+    type Item = T;
+    type Sieve = JoyePaillierSieve<T>;
+    fn make_sieve<R>(
+        &mut self,
+        rng: &mut R,
+        _previous_sieve: Option<&Self::Sieve>,
+    ) -> Result<Option<Self::Sieve>, Error>
+    where
+        R: CryptoRng + ?Sized,
+    {
+        let unit = self.pick_unit(rng);
+        let t = self.t_min.wrapping_add(&T::random_mod(rng, &self.t_count));
+        Ok(Some(JoyePaillierSieve {
+            unit,
+            offset: t.wrapping_mul(&self.modulus),
+            odd_modulus: self.odd_modulus.clone(),
+            remaining: self.sieve_length,
+        }))
+    }
+}
+
+/// Returns the multiplicative order of 2 modulo an odd prime `p`.
+fn order_of_two(p: u64) -> u64 {
+    let mut power = 2 % p;
+    let mut order = 1;
+    while power != 1 {
+        power = power * 2 % p;
+        order += 1;
+    }
+    order
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    a / x * b
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crypto_bigint::{BoxedUint, Limb, U64, U128, U1024, Word};
+    use num_prime::nt_funcs::is_prime64;
+
+    use super::JoyePaillierFactory;
+    use crate::{
+        Error, Flavor,
+        hazmat::{SieveFactory, precomputed::SMALL_PRIMES},
+        is_prime, is_prime_ct, sieve_and_find,
+    };
+
+    #[test]
+    fn joye_paillier() {
+        let mut rng = rand::rng();
+        for bit_length in [6, 7, 8, 10, 20, 40, 64] {
+            for _ in 0..10 {
+                let factory = JoyePaillierFactory::<U64>::new(bit_length).unwrap();
+                let p = sieve_and_find(&mut rng, factory, |_rng, num| is_prime_ct(num).into())
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(p.bits_vartime(), bit_length);
+                assert!(is_prime64(p.into()));
+            }
+        }
+
+        let factory = JoyePaillierFactory::<U1024>::new(1024).unwrap();
+        let p = sieve_and_find(&mut rng, factory, |_rng, num| is_prime_ct(num).into())
+            .unwrap()
+            .unwrap();
+        assert_eq!(p.bits_vartime(), 1024);
+        assert!(is_prime(Flavor::Any, &p));
+
+        let factory = JoyePaillierFactory::<BoxedUint>::new(200).unwrap();
+        let p = sieve_and_find(&mut rng, factory, |_rng, num| is_prime(Flavor::Any, num))
+            .unwrap()
+            .unwrap();
+        assert_eq!(p.bits_vartime(), 200);
+        assert!(is_prime(Flavor::Any, &p));
+    }
+
+    #[test]
+    fn joye_paillier_candidates() {
+        let mut rng = rand::rng();
+        let mut factory = JoyePaillierFactory::<U128>::new(128).unwrap();
+        // The modulus is the product of the primes up to 47.
+        let primes = SMALL_PRIMES
+            .iter()
+            .take_while(|p| **p <= 47)
+            .map(|p| Word::from(*p))
+            .collect::<Vec<_>>();
+        let rem = |x: &U128, p: Word| x.rem_limb(Limb(p).to_nz().unwrap()).0;
+
+        for _ in 0..10 {
+            let candidates = factory.make_sieve(&mut rng, None).unwrap().unwrap().collect::<Vec<_>>();
+            assert_eq!(candidates.len(), 128);
+            for candidate in candidates.iter() {
+                assert_eq!(candidate.bits_vartime(), 128);
+                assert_eq!(rem(candidate, 2), 1);
+                for p in primes.iter() {
+                    assert_ne!(rem(candidate, *p), 0);
+                }
+            }
+            // Every next candidate is twice the previous one modulo the odd part of the modulus.
+            for pair in candidates.windows(2) {
+                for p in primes.iter() {
+                    assert_eq!(rem(&pair[1], *p), (rem(&pair[0], *p) * 2) % p);
+                }
+            }
+        }
+
+        assert_eq!(
+            JoyePaillierFactory::<U64>::new(5).unwrap_err(),
+            Error::BitLengthTooSmall {
+                bit_length: 5,
+                flavor: Flavor::Any
+            }
+        );
+        assert_eq!(
+            JoyePaillierFactory::<U64>::new(65).unwrap_err(),
+            Error::BitLengthTooLarge {
+                bit_length: 65,
+                bits_precision: 64
+            }
+        );
+    }
+
+    #[test]
+    fn joye_paillier_no_repeats() {
+        let mut rng = rand::rng();
+        // The odd part of the modulus and the multiplicative order of 2 modulo it.
+        for (bit_length, odd_modulus, order) in [
+            (6, 3u32, 2u32),
+            (10, 15, 4),
+            (20, 105, 12),
+            (40, 255255, 120),
+            (64, 111546435, 3960),
+        ] {
+            let mut factory = JoyePaillierFactory::<U64>::new(bit_length).unwrap();
+            assert_eq!(factory.odd_modulus.as_ref(), &U64::from(odd_modulus));
+            for _ in 0..10 {
+                let mut candidates = factory
+                    .make_sieve(&mut rng, None)
+                    .unwrap()
+                    .unwrap()
+                    .map(u64::from)
+                    .collect::<Vec<_>>();
+                let expected_len = order.min(bit_length) as usize;
+                assert_eq!(candidates.len(), expected_len);
+                candidates.sort();
+                candidates.dedup();
+                assert_eq!(candidates.len(), expected_len);
+            }
+        }
+    }
+}
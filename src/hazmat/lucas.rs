@@ -1,16 +1,18 @@
 //! Lucas primality test.
 use core::num::NonZero;
-use crypto_bigint::{Limb, Monty, MontyMultiplier, Odd, Square, Unsigned, Word};
+use crypto_bigint::{Limb, Monty, MontyMultiplier, Odd, SquareAssign, Unsigned, Word};
 
 use super::{
     Primality,
     gcd::gcd_vartime,
     jacobi::{JacobiSymbol, jacobi_symbol_vartime},
+    wipe::{WipedInteger, WipedMonty},
 };
+use crate::error::Error;
 
 /// The maximum number of attempts to find `D` such that `(D/n) == -1`.
 // This is widely believed to be impossible.
-// So if we exceed it, we will panic reporting the value of `n`.
+// So if we exceed it, we report an internal error.
 const MAX_ATTEMPTS: usize = 10_000;
 
 /// The number of attempts to find `D` such that `(D/n) == -1`
@@ -28,7 +30,30 @@ pub trait LucasBase {
     /// Given an odd integer, returns `Ok((P, abs(Q), is_negative(Q)))` on success,
     /// or `Err(Primality)` if the primality for the given integer was discovered
     /// during the search for a base.
+    ///
+    /// May panic if a base could not be found (see [`try_generate`](`Self::try_generate`)).
     fn generate<T: Unsigned>(&self, n: &Odd<T>) -> Result<(Word, Word, bool), Primality>;
+
+    /// Same as [`generate`](`Self::generate`), but returns an error instead of panicking
+    /// if a base could not be found.
+    ///
+    /// The two levels of the result have different meanings:
+    /// - `Ok(Ok((P, abs(Q), is_negative(Q))))`: a base was found;
+    /// - `Ok(Err(primality))`: the primality of `n` was discovered during the search
+    ///   (same as the `Err` returned by [`generate`](`Self::generate`));
+    /// - `Err(error)`: the search failed, e.g. with [`Error::BaseNotFound`],
+    ///   and nothing is known about `n`.
+    ///
+    /// The default implementation forwards to [`generate`](`Self::generate`).
+    fn try_generate<T: Unsigned>(&self, n: &Odd<T>) -> Result<Result<(Word, Word, bool), Primality>, Error> {
+        Ok(self.generate(n))
+    }
+}
+
+/// Implements [`LucasBase::generate`] via [`LucasBase::try_generate`], panicking on errors.
+fn generate_or_panic<T: Unsigned>(base: &impl LucasBase, n: &Odd<T>) -> Result<(Word, Word, bool), Primality> {
+    base.try_generate(n)
+        .unwrap_or_else(|err| panic!("internal error: {err} (n = {n:?})"))
 }
 
 /// "Method A" for selecting the base given in Baillie & Wagstaff[^Baillie1980],
@@ -46,6 +71,10 @@ pub struct SelfridgeBase;
 
 impl LucasBase for SelfridgeBase {
     fn generate<T: Unsigned>(&self, n: &Odd<T>) -> Result<(Word, Word, bool), Primality> {
+        generate_or_panic(self, n)
+    }
+
+    fn try_generate<T: Unsigned>(&self, n: &Odd<T>) -> Result<Result<(Word, Word, bool), Primality>, Error> {
         let mut abs_d = 5;
         let mut d_is_negative = false;
         let n_is_small = n.bits_vartime() < Word::BITS; // if true, `n` fits into one `Word`
@@ -53,13 +82,13 @@ impl LucasBase for SelfridgeBase {
         let mut attempts = 0;
         loop {
             if attempts >= MAX_ATTEMPTS {
-                panic!("internal error: cannot find (D/n) = -1 for {:?}", n)
+                return Err(Error::BaseNotFound { attempts });
             }
 
             if attempts >= ATTEMPTS_BEFORE_SQRT {
                 let sqrt_n = n.sqrt_vartime();
                 if &sqrt_n.wrapping_mul(&sqrt_n) == n.as_ref() {
-                    return Err(Primality::Composite);
+                    return Ok(Err(Primality::Composite));
                 }
             }
 
@@ -76,7 +105,7 @@ impl LucasBase for SelfridgeBase {
                 // enables 5 and 11 to be classified as Lucas probable primes.
                 // Otherwise GCD(D, n) > 1, and therefore n is not prime.
                 if !(n_is_small && small_n == abs_d) {
-                    return Err(Primality::Composite);
+                    return Ok(Err(Primality::Composite));
                 }
             }
 
@@ -93,7 +122,7 @@ impl LucasBase for SelfridgeBase {
             ((abs_d - 1) / 4, true)
         };
 
-        Ok((1, abs_q, q_is_negative))
+        Ok(Ok((1, abs_q, q_is_negative)))
     }
 }
 
@@ -110,13 +139,17 @@ pub struct AStarBase;
 
 impl LucasBase for AStarBase {
     fn generate<T: Unsigned>(&self, n: &Odd<T>) -> Result<(Word, Word, bool), Primality> {
-        SelfridgeBase.generate(n).map(|(p, abs_q, q_is_negative)| {
+        generate_or_panic(self, n)
+    }
+
+    fn try_generate<T: Unsigned>(&self, n: &Odd<T>) -> Result<Result<(Word, Word, bool), Primality>, Error> {
+        Ok(SelfridgeBase.try_generate(n)?.map(|(p, abs_q, q_is_negative)| {
             if abs_q == 1 && q_is_negative {
                 (5, 5, false)
             } else {
                 (p, abs_q, q_is_negative)
             }
-        })
+        }))
     }
 }
 
@@ -132,18 +165,22 @@ pub struct BruteForceBase;
 
 impl LucasBase for BruteForceBase {
     fn generate<T: Unsigned>(&self, n: &Odd<T>) -> Result<(Word, Word, bool), Primality> {
+        generate_or_panic(self, n)
+    }
+
+    fn try_generate<T: Unsigned>(&self, n: &Odd<T>) -> Result<Result<(Word, Word, bool), Primality>, Error> {
         let mut p = 3;
         let mut attempts = 0;
 
         loop {
             if attempts >= MAX_ATTEMPTS {
-                panic!("internal error: cannot find (D/n) = -1 for {:?}", n)
+                return Err(Error::BaseNotFound { attempts });
             }
 
             if attempts >= ATTEMPTS_BEFORE_SQRT {
                 let sqrt_n = n.sqrt_vartime();
                 if &sqrt_n.wrapping_mul(&sqrt_n) == n.as_ref() {
-                    return Err(Primality::Composite);
+                    return Ok(Err(Primality::Composite));
                 }
             }
 
@@ -164,14 +201,14 @@ impl LucasBase for BruteForceBase {
                 } else {
                     Primality::Composite
                 };
-                return Err(primality);
+                return Ok(Err(primality));
             }
 
             attempts += 1;
             p += 1;
         }
 
-        Ok((p, 1, false))
+        Ok(Ok((p, 1, false)))
     }
 }
 
@@ -317,8 +354,36 @@ pub enum LucasCheck {
 /// When used with [`SelfridgeBase`] and [`LucasCheck::Regular`], implements the algorithm
 /// prescribed by the FIPS.186-5 standard[^FIPS].
 ///
+/// If the `zeroize` feature is enabled, the terms `U_k`, `V_k`, `Q^k` and the index `d`
+/// are wiped before returning. The Montgomery parameters and the Montgomery forms of `P`, `Q` and `D` are not.
+///
 /// [^FIPS]: FIPS-186.5 standard, <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf>
+///
+/// Panics if `base` fails to find a base (see [`try_lucas_test`]).
 pub fn lucas_test<T>(candidate: Odd<T>, base: impl LucasBase, check: LucasCheck) -> Primality
+where
+    T: Unsigned,
+{
+    match base.generate(&candidate) {
+        Ok(pq) => lucas_test_with_base(candidate, pq, check),
+        Err(primality) => primality,
+    }
+}
+
+/// Same as [`lucas_test`], but returns an error instead of panicking if `base` fails to find a base
+/// (see [`LucasBase::try_generate`]).
+pub fn try_lucas_test<T>(candidate: Odd<T>, base: impl LucasBase, check: LucasCheck) -> Result<Primality, Error>
+where
+    T: Unsigned,
+{
+    Ok(match base.try_generate(&candidate)? {
+        Ok(pq) => lucas_test_with_base(candidate, pq, check),
+        Err(primality) => primality,
+    })
+}
+
+/// Performs the Lucas test with the given base `(P, abs(Q), is_negative(Q))`.
+fn lucas_test_with_base<T>(candidate: Odd<T>, base: (Word, Word, bool), check: LucasCheck) -> Primality
 where
     T: Unsigned,
 {
@@ -331,11 +396,7 @@ where
     // A word-to-big integer conversion helper
     let to_integer = |x: Word| T::from_limb_like(Limb::from(x), candidate.as_ref());
 
-    // Find the base for the Lucas sequence.
-    let (p, abs_q, q_is_negative) = match base.generate(&candidate) {
-        Ok(pq) => pq,
-        Err(primality) => return primality,
-    };
+    let (p, abs_q, q_is_negative) = base;
 
     // Discriminant `d = p^2 - 4q`
     let (abs_d, d_is_negative) = if q_is_negative {
@@ -372,7 +433,9 @@ where
 
     // Find `d` and `s`, such that `d` is odd and `d * 2^s = n - (D/n)`.
     // Since `(D/n) == -1` by construction, we're looking for `d * 2^s = n + 1`.
+    // `d` is wiped on drop if the `zeroize` feature is enabled.
     let (s, d) = decompose(&candidate);
+    let d = WipedInteger(d.get());
 
     // Some constants in Montgomery form
     let params = <T as Unsigned>::Monty::new_params_vartime(candidate.clone());
@@ -414,11 +477,12 @@ where
     // We can therefore start with k=0 and build up to k=d in log2(d) steps.
 
     // Starting with k = 0
-    let mut vk = two.clone(); // keeps V_k
-    let mut uk = <T as Unsigned>::Monty::zero(params.clone()); // keeps U_k
-    let mut qk = one.clone(); // keeps Q^k
+    // The terms of the sequence are wiped on drop if the `zeroize` feature is enabled.
+    let mut vk = WipedMonty(two.clone()); // keeps V_k
+    let mut uk = WipedMonty(<T as Unsigned>::Monty::zero(params.clone())); // keeps U_k
+    let mut qk = WipedMonty(one.clone()); // keeps Q^k
 
-    let mut temp = <T as Unsigned>::Monty::zero(params.clone());
+    let mut temp = WipedMonty(<T as Unsigned>::Monty::zero(params.clone()));
 
     let mut mm = <<T as Unsigned>::Monty as Monty>::Multiplier::from(&params);
 
@@ -435,8 +499,8 @@ where
         mm.mul_assign(&mut uk, &vk);
 
         mm.square_assign(&mut vk);
-        vk -= &qk;
-        vk -= &qk;
+        *vk -= &*qk;
+        *vk -= &*qk;
 
         mm.square_assign(&mut qk);
 
@@ -450,14 +514,14 @@ where
             if !p_is_one {
                 mm.mul_assign(&mut uk, &p);
             }
-            uk += &vk;
+            *uk += &*vk;
             uk.div_by_2_assign();
 
             mm.mul_assign(&mut temp, &d_m);
             if !p_is_one {
                 mm.mul_assign(&mut vk, &p);
             };
-            vk += &temp;
+            *vk += &*temp;
             vk.div_by_2_assign();
 
             mm.mul_assign(&mut qk, &q);
@@ -467,7 +531,7 @@ where
     // Now k=d, so vk = V_d and uk = U_d.
 
     // The `U_d == 0` criterion.
-    let ud_equals_zero = uk == zero;
+    let ud_equals_zero = *uk == zero;
 
     // The `V_d == ±2 mod n` criterion.
     //
@@ -478,7 +542,7 @@ where
     //
     // If `Q != 1` we just consider it passed (we don't have a corresponding
     // pseudoprime list anyway).
-    let vk_equals_two = !q_is_one || (vk == two || vk == minus_two);
+    let vk_equals_two = !q_is_one || (*vk == two || *vk == minus_two);
 
     // Early exit for some of the checks.
     if check == LucasCheck::Strong && ud_equals_zero {
@@ -499,7 +563,7 @@ where
     // Propagate `V_k` up to `V_{n+1}`.
     // For the checks which require it, check if V_{2^t d} == 0 mod n for some 0 <= t < s.
 
-    let mut one_of_vk_equals_zero = vk == zero;
+    let mut one_of_vk_equals_zero = *vk == zero;
 
     if (check == LucasCheck::Strong || check == LucasCheck::ExtraStrong || check == LucasCheck::AlmostExtraStrong)
         && one_of_vk_equals_zero
@@ -515,20 +579,21 @@ where
             || check == LucasCheck::AlmostExtraStrong
             || check == LucasCheck::Bpsw21)
             && q_is_one
-            && (vk == two || vk == minus_two)
+            && (*vk == two || *vk == minus_two)
         {
             return Primality::Composite;
         }
 
         if check == LucasCheck::Regular {
-            uk *= &vk;
+            *uk *= &*vk;
         }
 
         // k' = 2k
         // V_{k'} = V_k^2 - 2 Q^k
-        vk = vk.square() - &qk.double();
+        vk.square_assign();
+        *vk -= &qk.double();
 
-        one_of_vk_equals_zero |= vk == zero;
+        one_of_vk_equals_zero |= *vk == zero;
 
         if (check == LucasCheck::Strong || check == LucasCheck::ExtraStrong || check == LucasCheck::AlmostExtraStrong)
             && one_of_vk_equals_zero
@@ -537,7 +602,7 @@ where
         }
 
         if !q_is_one {
-            qk = qk.square();
+            qk.square_assign();
         }
     }
 
@@ -557,8 +622,8 @@ where
 
     if check == LucasCheck::Regular {
         // Double the index again:
-        uk *= &vk; // now `uk = U_{d * 2^s} = U_{n+1}`
-        if uk == zero {
+        *uk *= &*vk; // now `uk = U_{d * 2^s} = U_{n+1}`
+        if *uk == zero {
             return Primality::ProbablyPrime;
         } else {
             return Primality::Composite;
@@ -566,10 +631,13 @@ where
     }
 
     // Double the index again:
-    vk = vk.square() - &qk - &qk; // now `vk = V_{d * 2^s} = V_{n+1}`
+    // now `vk = V_{d * 2^s} = V_{n+1}`
+    vk.square_assign();
+    *vk -= &*qk;
+    *vk -= &*qk;
 
     // Lucas-V check[^Baillie2021]: if `V_{n+1} != 2 Q`, report `n` as composite.
-    let lucas_v = vk == q.double();
+    let lucas_v = *vk == q.double();
     if check == LucasCheck::LucasV {
         if !lucas_v {
             return Primality::Composite;
@@ -594,7 +662,7 @@ where
         JacobiSymbol::MinusOne => -q,
     };
 
-    if qk == t {
+    if *qk == t {
         Primality::ProbablyPrime
     } else {
         Primality::Composite
@@ -611,8 +679,13 @@ mod tests {
     #[cfg(feature = "tests-exhaustive")]
     use num_prime::nt_funcs::is_prime64;
 
-    use super::{AStarBase, BruteForceBase, LucasBase, LucasCheck, SelfridgeBase, decompose, lucas_test};
-    use crate::hazmat::{Primality, primes, pseudoprimes};
+    use super::{
+        AStarBase, BruteForceBase, LucasBase, LucasCheck, SelfridgeBase, decompose, lucas_test, try_lucas_test,
+    };
+    use crate::{
+        Error,
+        hazmat::{Primality, primes, pseudoprimes},
+    };
 
     #[test]
     fn bases_derived_traits() {
@@ -666,6 +739,30 @@ mod tests {
             lucas_test(Odd::new(U64::from(15u32)).unwrap(), TestBase, LucasCheck::Strong),
             Primality::Composite
         );
+        // The default `try_generate()` forwards to `generate()`.
+        assert_eq!(
+            try_lucas_test(Odd::new(U64::from(15u32)).unwrap(), TestBase, LucasCheck::Strong),
+            Ok(Primality::Composite)
+        );
+    }
+
+    #[test]
+    fn base_not_found() {
+        struct TestBase;
+
+        impl LucasBase for TestBase {
+            fn generate<T: Unsigned>(&self, _n: &Odd<T>) -> Result<(Word, Word, bool), Primality> {
+                unreachable!()
+            }
+            fn try_generate<T: Unsigned>(&self, _n: &Odd<T>) -> Result<Result<(Word, Word, bool), Primality>, Error> {
+                Err(Error::BaseNotFound { attempts: 1 })
+            }
+        }
+
+        assert_eq!(
+            try_lucas_test(Odd::new(U64::from(15u32)).unwrap(), TestBase, LucasCheck::Strong),
+            Err(Error::BaseNotFound { attempts: 1 })
+        );
     }
 
     #[test]
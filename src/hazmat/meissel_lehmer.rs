@@ -0,0 +1,256 @@
+//! Exact prime counting for `u64` arguments with the Meissel-Lehmer method.
+
+use alloc::{vec, vec::Vec};
+
+use crypto_bigint::U64;
+
+use super::eratosthenes::SegmentedSieve;
+use crate::presets::{Flavor, next_prime, prev_prime};
+
+// The largest argument of `pi()` for which the primes are sieved directly.
+const DIRECT_SIEVE_LIMIT: u64 = 1 << 20;
+
+// For larger arguments the primes are sieved up to `x^(2/3)`, but not further than this limit
+// (unless it is smaller than `sqrt(x)`, which is necessary for the method).
+// This value only affects the trade-off between the memory and the speed.
+const SIEVE_LIMIT: u64 = 1 << 28;
+
+// The number of primes whose product is used for the fast calculation of `phi(x, a)` for small `a`.
+const PHI_TABLE_PRIMES: usize = 6;
+
+// The product of the first `PHI_TABLE_PRIMES` primes.
+const PRIMORIAL: u64 = 2 * 3 * 5 * 7 * 11 * 13;
+
+// The number of integers in `[1, PRIMORIAL]` coprime with `PRIMORIAL`.
+const PRIMORIAL_TOTIENT: u64 = 2 * 4 * 6 * 10 * 12;
+
+// `pi(2^64 - 1)`
+const PI_U64_MAX: u64 = 425656284035217743;
+
+struct PrimeCounter {
+    // All the primes up to `limit`.
+    primes: Vec<u32>,
+    limit: u64,
+    // `phi_table[r]` is the number of integers in `[1, r]` coprime with `PRIMORIAL`.
+    phi_table: Vec<u16>,
+}
+
+impl PrimeCounter {
+    fn new(x: u64) -> Self {
+        let limit = if x <= DIRECT_SIEVE_LIMIT {
+            x
+        } else {
+            (icbrt(x) * icbrt(x)).min(SIEVE_LIMIT).max(x.isqrt())
+        };
+        let primes = SegmentedSieve::new(limit)
+            .map(|p| u32::try_from(p).expect("the sieving limit fits in `u32`"))
+            .collect::<Vec<_>>();
+
+        let mut phi_table = vec![0u16; PRIMORIAL as usize];
+        let mut count = 0;
+        for (r, entry) in phi_table.iter_mut().enumerate().skip(1) {
+            if [2, 3, 5, 7, 11, 13].iter().all(|p| r % p != 0) {
+                count += 1;
+            }
+            *entry = count;
+        }
+
+        Self {
+            primes,
+            limit,
+            phi_table,
+        }
+    }
+
+    // The `i`-th prime (1-based), assuming it is within the sieved range.
+    fn prime(&self, i: u64) -> u64 {
+        u64::from(self.primes[(i - 1) as usize])
+    }
+
+    fn pi(&self, x: u64) -> u64 {
+        if x <= self.limit {
+            return self.primes.partition_point(|p| u64::from(*p) <= x) as u64;
+        }
+
+        // Lehmer's formula[^Lehmer]:
+        //   pi(x) = phi(x, a) + (b + a - 2)(b - a + 1) / 2
+        //           - sum_{a < i <= b} pi(x / p_i)
+        //           - sum_{a < i <= c} sum_{i <= j <= b_i} (pi(x / (p_i p_j)) - (j - 1)),
+        // where `a = pi(x^(1/4))`, `b = pi(x^(1/2))`, `c = pi(x^(1/3))`, `b_i = pi((x / p_i)^(1/2))`.
+        let a = self.pi(x.isqrt().isqrt());
+        let b = self.pi(x.isqrt());
+        let c = self.pi(icbrt(x));
+
+        // Note that the result may be temporarily negative.
+        let mut sum = self.phi(x, a) as i128 + ((b + a - 2) as i128 * (b - a + 1) as i128) / 2;
+        for i in (a + 1)..=b {
+            let w = x / self.prime(i);
+            sum -= self.pi(w) as i128;
+            if i <= c {
+                let b_i = self.pi(w.isqrt());
+                for j in i..=b_i {
+                    sum -= self.pi(w / self.prime(j)) as i128 - (j as i128 - 1);
+                }
+            }
+        }
+        u64::try_from(sum).expect("the prime count is non-negative")
+    }
+
+    // The number of integers in `[1, x]` not divisible by any of the first `a` primes.
+    fn phi(&self, x: u64, a: u64) -> u64 {
+        if a as usize <= PHI_TABLE_PRIMES {
+            return self.phi_small(x, a);
+        }
+        if x <= self.prime(a) {
+            return if x == 0 { 0 } else { 1 };
+        }
+        // If `x < p_(a+1)^2`, the only numbers not divisible by the first `a` primes are 1
+        // and the primes greater than `p_a`.
+        if x <= self.limit && (a as usize) < self.primes.len() && x < self.prime(a + 1).pow(2) {
+            return self.pi(x) - a + 1;
+        }
+
+        // phi(x, a) = phi(x, a - 1) - phi(x / p_a, a - 1), unrolled down to `PHI_TABLE_PRIMES`.
+        let mut result = self.phi_small(x, PHI_TABLE_PRIMES as u64);
+        for i in (PHI_TABLE_PRIMES as u64 + 1)..=a {
+            result -= self.phi(x / self.prime(i), i - 1);
+        }
+        result
+    }
+
+    fn phi_small(&self, x: u64, a: u64) -> u64 {
+        if a == 0 {
+            return x;
+        }
+        if a as usize == PHI_TABLE_PRIMES {
+            return (x / PRIMORIAL) * PRIMORIAL_TOTIENT + u64::from(self.phi_table[(x % PRIMORIAL) as usize]);
+        }
+        self.phi_small(x, a - 1) - self.phi_small(x / self.prime(a), a - 1)
+    }
+}
+
+/// Returns `floor(x^(1/3))`.
+fn icbrt(x: u64) -> u64 {
+    let mut root = libm::cbrt(x as f64) as u64;
+    // Correct the floating point error.
+    while root.checked_pow(3).is_none_or(|cube| cube > x) {
+        root -= 1;
+    }
+    while (root + 1).checked_pow(3).is_some_and(|cube| cube <= x) {
+        root += 1;
+    }
+    root
+}
+
+/// Returns the exact number of primes less than or equal to `x`.
+///
+/// Uses the Meissel-Lehmer method in the form given by Lehmer[^Lehmer],
+/// with the primes up to `x^(2/3)` (but at least up to `sqrt(x)`) found with a [`SegmentedSieve`].
+/// The time complexity is roughly `O(x / ln^4 x)`; for `x` close to `2^64`
+/// the computation is very expensive in both time and memory.
+///
+/// [^Lehmer]: D. H. Lehmer, "On the exact number of primes less than a given limit",
+///   Illinois J. Math. 3(3) 381-388 (1959),
+///   DOI: [10.1215/ijm/1255455259](https://doi.org/10.1215/ijm/1255455259)
+pub fn primecount_exact(x: u64) -> u64 {
+    PrimeCounter::new(x).pi(x)
+}
+
+/// Returns the `n`-th prime (1-based, so `nth_prime(1) == Some(2)`).
+///
+/// The prime is found by estimating its value, refining the estimate with [`primecount_exact`],
+/// and walking to the exact prime from there.
+///
+/// Returns `None` if `n` is zero or the `n`-th prime does not fit in `u64`.
+pub fn nth_prime(n: u64) -> Option<u64> {
+    if n == 0 || n > PI_U64_MAX {
+        return None;
+    }
+    if n < 6 {
+        return Some([2, 3, 5, 7, 11][(n - 1) as usize]);
+    }
+
+    // Cipolla's asymptotic expansion:
+    //   p_n ~ n (ln n + ln ln n - 1 + (ln ln n - 2) / ln n)
+    let n_f = n as f64;
+    let ln_n = libm::log(n_f);
+    let ln_ln_n = libm::log(ln_n);
+    let mut guess = (n_f * (ln_n + ln_ln_n - 1. + (ln_ln_n - 2.) / ln_n)) as u64;
+
+    // Refine the estimate using the density of primes around it, until it is close enough to walk the rest.
+    let mut count = primecount_exact(guess);
+    for _ in 0..4 {
+        if count.abs_diff(n) < 1000 {
+            break;
+        }
+        let ln_guess = libm::log(guess as f64);
+        let offset = (count.abs_diff(n) as f64 * ln_guess) as u64;
+        guess = if count > n {
+            guess.saturating_sub(offset)
+        } else {
+            guess.saturating_add(offset)
+        };
+        count = primecount_exact(guess);
+    }
+
+    // Walk to the `n`-th prime.
+    if count >= n {
+        let mut prime = prev_prime(Flavor::Any, &U64::from(guess)).ok()?;
+        while count > n {
+            prime = prev_prime(Flavor::Any, &prime.wrapping_sub(&U64::ONE)).ok()?;
+            count -= 1;
+        }
+        Some(prime.into())
+    } else {
+        let mut prime = U64::from(guess);
+        while count < n {
+            prime = next_prime(Flavor::Any, &prime.wrapping_add(&U64::ONE)).ok()?;
+            count += 1;
+        }
+        Some(prime.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{icbrt, nth_prime, primecount_exact};
+    use crate::hazmat::SegmentedSieve;
+
+    #[test]
+    fn cube_root() {
+        for x in (0u64..100_000).chain([u64::MAX, u64::MAX - 1, 2642245u64.pow(3), 2642245u64.pow(3) - 1]) {
+            let root = icbrt(x);
+            assert!(root.pow(3) <= x);
+            assert!((root + 1).checked_pow(3).is_none_or(|cube| cube > x));
+        }
+    }
+
+    #[test]
+    fn small_values() {
+        let primes = SegmentedSieve::new(100_000).collect::<Vec<_>>();
+        for x in (0..500).chain((500..100_000).step_by(997)) {
+            assert_eq!(primecount_exact(x), primes.partition_point(|p| *p <= x) as u64);
+        }
+        for (i, p) in primes.iter().enumerate().step_by(97) {
+            assert_eq!(nth_prime(i as u64 + 1), Some(*p));
+        }
+    }
+
+    #[test]
+    fn known_values() {
+        // From https://oeis.org/A006880 and https://oeis.org/A006988
+        assert_eq!(primecount_exact(1_000_000_000), 50847534);
+        assert_eq!(primecount_exact(10_000_000_000), 455052511);
+        assert_eq!(primecount_exact(1 << 32), 203280221);
+        assert_eq!(nth_prime(1_000_000), Some(15485863));
+        assert_eq!(nth_prime(100_000_000), Some(2038074743));
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(nth_prime(0), None);
+        assert_eq!(nth_prime(425656284035217744), None);
+    }
+}
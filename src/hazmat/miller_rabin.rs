@@ -1,11 +1,14 @@
 //! Miller-Rabin primality test.
 
-use crypto_bigint::{Limb, Monty, NonZero as CTNonZero, Odd, PowBoundedExp, RandomMod, Square, Unsigned};
+use crypto_bigint::{Limb, Monty, NonZero as CTNonZero, Odd, PowBoundedExp, RandomMod, SquareAssign, Unsigned};
 use rand_core::CryptoRng;
 
+#[cfg(feature = "zeroize")]
+use super::wipe::{wipe_integer, wipe_monty};
 use super::{
     Primality, equals_primitive,
     float::{floor_sqrt, two_powf_upper_bound, two_powi},
+    wipe::WipedMonty,
 };
 
 /// Precomputed data used to perform Miller-Rabin primality test[^Pomerance1980].
@@ -15,6 +18,9 @@ use super::{
 ///
 /// The implementation satisfies the FIPS.186-5 standard[^FIPS].
 ///
+/// If the `zeroize` feature is enabled, the secret state is wiped on drop (see the `Zeroize` implementation),
+/// along with the intermediate powers computed in [`MillerRabin::test`].
+///
 /// [^Pomerance1980]: C. Pomerance, J. L. Selfridge, S. S. Wagstaff "The Pseudoprimes to 25*10^9",
 ///   Math. Comp. 35 1003-1026 (1980),
 ///   DOI: [10.2307/2006210](https://dx.doi.org/10.2307/2006210)
@@ -79,23 +85,26 @@ impl<T: Unsigned + RandomMod> MillerRabin<T> {
         // One could check here if `gcd(base, candidate) == 1` and return `Composite` otherwise.
         // In practice it doesn't make any performance difference in normal operation.
 
-        let base = <T as Unsigned>::Monty::new(base.clone(), self.montgomery_params.clone());
+        let base = WipedMonty(<T as Unsigned>::Monty::new(
+            base.clone(),
+            self.montgomery_params.clone(),
+        ));
 
         // Implementation detail: bounded exp gets faster every time we decrease the bound
         // by the window length it uses, which is currently 4 bits.
         // So even when the bound isn't low enough that the number can fit
         // in a smaller number of limbs, there is still a performance gain
         // from specifying the bound.
-        let mut test = base.pow_bounded_exp(&self.d, self.bits);
+        let mut test = WipedMonty(base.pow_bounded_exp(&self.d, self.bits));
 
-        if test == self.one || test == self.minus_one {
+        if *test == self.one || *test == self.minus_one {
             return Primality::ProbablyPrime;
         }
         for _ in 1..self.s {
-            test = test.square();
-            if test == self.one {
+            test.square_assign();
+            if *test == self.one {
                 return Primality::Composite;
-            } else if test == self.minus_one {
+            } else if *test == self.minus_one {
                 return Primality::ProbablyPrime;
             }
         }
@@ -136,6 +145,32 @@ impl<T: Unsigned + RandomMod> MillerRabin<T> {
     }
 }
 
+/// Wipes the candidate, `d`, `s`, and the numbers 1 and -1 in Montgomery form.
+///
+/// The Montgomery parameters (which contain the candidate as the modulus) are left intact,
+/// since `crypto-bigint` does not provide a way to wipe them for a generic integer type.
+#[cfg(feature = "zeroize")]
+impl<T: Unsigned> zeroize::Zeroize for MillerRabin<T> {
+    fn zeroize(&mut self) {
+        wipe_integer(&mut self.candidate);
+        wipe_integer(&mut self.d);
+        self.s.zeroize();
+        self.bits.zeroize();
+        wipe_monty(&mut self.one);
+        wipe_monty(&mut self.minus_one);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: Unsigned> Drop for MillerRabin<T> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: Unsigned> zeroize::ZeroizeOnDrop for MillerRabin<T> {}
+
 /**
 Returns the probability `p_{k,t}` of an odd `k`-bit integer passing `t` rounds of MR testing with random bases
 is actually composite.
@@ -243,6 +278,21 @@ mod tests {
     use super::{MillerRabin, minimum_mr_iterations};
     use crate::hazmat::{Primality, SetBits, SmallFactorsSieve, primes, pseudoprimes, random_odd_integer};
 
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn zeroize() {
+        use zeroize::Zeroize;
+
+        let mut mr = MillerRabin::new(Odd::new(U64::from(1009u32)).unwrap());
+        assert!(mr.test_base_two().is_probably_prime());
+        mr.zeroize();
+        assert_eq!(mr.candidate, U64::ZERO);
+        assert_eq!(mr.d, U64::ZERO);
+        assert_eq!(mr.s, 0);
+        assert_eq!(mr.one.as_montgomery(), &U64::ZERO);
+        assert_eq!(mr.minus_one.as_montgomery(), &U64::ZERO);
+    }
+
     #[test]
     fn miller_rabin_derived_traits() {
         let mr = MillerRabin::new(Odd::new(U64::ONE).unwrap());
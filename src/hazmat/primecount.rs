@@ -1,11 +1,35 @@
-use crate::hazmat::float::ln;
+use crate::hazmat::{
+    SMALL_PRIMES,
+    float::{ln, low_u128},
+    primecount_exact,
+};
 use core::f64;
-use crypto_bigint::{Concat, NonZero, Split, Uint};
+use crypto_bigint::{Limb, NonZero, Unsigned};
+
+/// Below this value Dusart's lower bound is not valid, and the exact count is cheap to compute.
+const DUSART_LOWER_BOUND_MIN: u64 = 88789;
+
+/// Above this value Dusart's tighter 2010 upper bound holds.
+const DUSART_UPPER_BOUND_2010_MIN: u64 = 2953652287;
+
+/// The maximum number of Newton iterations when inverting `Li(x)`.
+const INVERSE_LI_MAX_ITERATIONS: usize = 16;
+
+/// Newton iterations stop when the step is this many bits smaller than the current value.
+const INVERSE_LI_CONVERGED_BITS: u32 = 48;
+
+/// Relative error allowance for the floating point parts of the bound calculations.
+///
+/// `ln()` is accurate to about $2^{-50}$, so this comfortably covers its error and the rounding of the series sums.
+const FLOAT_SLACK: f64 = 1. / (1u64 << 40) as f64;
 
 /// Estimate the number of primes smaller than x using the asymptotic expansion of `Li(x)` with 4 terms, i.e.:
 ///
 ///   $$\pi(x) \approx \frac{x}{\ln x} \left(1 + \frac{1!}{\ln x} + \frac{2!}{\ln^2 x} + \frac{3!}{\ln^3 x}\right)$$
 ///
+/// The estimate is not guaranteed to be either above or below the true value;
+/// see [`primecount_bounds`] for an interval that is guaranteed to contain $\pi(x)$.
+///
 /// For values of x up to ~$10^{29}$, consider using precalculated values for $\pi(x)$ from
 /// e.g. <https://sweet.ua.pt/tos/primes.html>.
 ///
@@ -73,78 +97,294 @@ use crypto_bigint::{Concat, NonZero, Split, Uint};
 ///
 /// [^Trudgian2014]: T. Trudgian, "Updating the error term in the prime number theorem",
 ///   [arXiv:1401.2689](https://arxiv.org/abs/1401.2689) (2014)
-pub fn estimate_primecount<const LIMBS: usize, const RHS_LIMBS: usize>(x: &Uint<LIMBS>) -> Uint<LIMBS>
-where
-    Uint<LIMBS>: Concat<Output = Uint<RHS_LIMBS>>,
-    Uint<RHS_LIMBS>: Split<Output = Uint<LIMBS>>,
-{
-    // Number of bits to scale by (fractional bits during division)
-    // On 64 bit CPUs and x is a U64, we use 32 bits (1 limb * 32). Else 64.
-    // On 32 bit CPUs and x is a U64, we use 32 bits (2 limb * 16). Else 64.
-    #[cfg(target_pointer_width = "32")]
-    let scale_bits = (LIMBS as u32 * 16).min(64);
-    #[cfg(target_pointer_width = "64")]
-    let scale_bits = (LIMBS as u32 * 32).min(64);
-    let total_scale_bits = 2 * scale_bits;
-    // Scaling factor for the denominators of the expansion terms, 2^64.
-    let denom_scale_factor = (1u128 << scale_bits) as f64;
-
+pub fn estimate_primecount<T: Unsigned>(x: &T) -> T {
     let ln_x = ln(x);
 
     if !ln_x.is_finite() || ln_x <= 1.0 {
-        return Uint::ZERO;
+        return T::zero_like(x);
     }
 
-    // Calculate f64 denominators L, L^2, L^3, L^4
+    // Calculate the expansion `(1 + 1!/L + 2!/L^2 + 3!/L^3) / L` in floating point
+    // and apply it to `x` in integer arithmetic.
     let ln_x_2 = ln_x * ln_x;
     let ln_x_3 = ln_x_2 * ln_x;
-    let ln_x_4 = ln_x_3 * ln_x;
-
-    // Scale up a float by 2^64, round, cast to u128 and then to a wide `Uint`.
-    let f64_to_scaled_uint = |value: f64| -> NonZero<Uint<RHS_LIMBS>> {
-        let scaled = value * denom_scale_factor;
-        let scaled = libm::round(scaled).max(1.0) as u128;
-        let denom = Uint::<RHS_LIMBS>::from_u128(scaled);
-        NonZero::new(denom).expect("max(1.0) ensures value is at least 1")
+    let factor = (1. + 1. / ln_x + 2. / ln_x_2 + 6. / ln_x_3) / ln_x;
+
+    mul_f64(x, factor, false)
+}
+
+/// Returns a `(lower, upper)` pair such that `lower <= π(x) <= upper`, where π(x) is the number of primes not exceeding x.
+///
+/// The bounds are Dusart's unconditional inequalities[^Dusart2010][^Dusart2018]:
+///
+///   $$\frac{x}{\ln x} \left(1 + \frac{1}{\ln x} + \frac{2}{\ln^2 x}\right) \le \pi(x), \quad x \ge 88789,$$
+///   $$\pi(x) \le \frac{x}{\ln x} \left(1 + \frac{1}{\ln x} + \frac{c}{\ln^2 x}\right),$$
+///
+/// with $c = 2.334$ for $x \ge 2953652287$ and $c = 2.53816$ for all $x > 1$.
+/// For $x < 88789$ both bounds are equal to the exact value of $\pi(x)$.
+///
+/// The floating point parts of the calculation are rounded outwards, so the bounds hold
+/// regardless of the precision of `f64`.
+/// The relative width of the interval is about $0.5 / \ln^2 x$, e.g. $\approx 2^{-20}$ for 1024-bit `x`.
+///
+/// [^Dusart2010]: P. Dusart, "Estimates of Some Functions Over Primes without R.H.",
+///   [arXiv:1002.0442](https://arxiv.org/pdf/1002.0442) (2010)
+///
+/// [^Dusart2018]: P. Dusart, "Explicit estimates of some functions over primes",
+///   The Ramanujan Journal 45(1) 227-251 (2018),
+///   DOI: [10.1007/s11139-016-9839-4](https://link.springer.com/article/10.1007/s11139-016-9839-4)
+pub fn primecount_bounds<T: Unsigned>(x: &T) -> (T, T) {
+    let x_u64 = to_u64(x);
+    if let Some(x_u64) = x_u64 {
+        if x_u64 < DUSART_LOWER_BOUND_MIN {
+            let count = from_u64_like(primecount_exact(x_u64), x);
+            return (count.clone(), count);
+        }
+    }
+
+    let ln_x = ln(x);
+    let upper_c = if x_u64.is_none_or(|x| x >= DUSART_UPPER_BOUND_2010_MIN) {
+        2.334
+    } else {
+        2.53816
     };
+    let factor = |c: f64| (1. + 1. / ln_x + c / (ln_x * ln_x)) / ln_x;
+
+    let lower = mul_f64(x, factor(2.) * (1. - FLOAT_SLACK), false);
+    let upper = mul_f64(x, factor(upper_c) * (1. + FLOAT_SLACK), true);
+    (lower, upper)
+}
+
+/// Returns a `(lower, upper)` pair such that `lower <= π(x) <= upper`, assuming the Riemann hypothesis.
+///
+/// Uses Schoenfeld's bound[^Schoenfeld1976] $|\pi(x) - \text{li}(x)| < \frac{\sqrt{x} \ln x}{8\pi}$,
+/// with $\text{li}(x)$ evaluated using $N = \lfloor \ln x / 4 \rfloor - 1$ terms of its asymptotic expansion.
+/// Splitting the integral at $\sqrt{x}$ bounds the truncation error by
+/// $\frac{2 x}{\ln x} \frac{N!}{\ln^N x} + \sqrt{x}$, which is added to the interval.
+///
+/// In practice the width of the interval is limited by the precision of `f64`,
+/// giving a relative width of about $2^{-39}$ for `x` above $2^{128}$.
+/// The result is intersected with [`primecount_bounds`], which is also returned as is for `x` below $2^{64}$,
+/// where the expansion converges too slowly to improve on it.
+///
+/// [^Schoenfeld1976]: L. Schoenfeld, "Sharper Bounds for the Chebyshev Functions θ(x) and ψ(x). II",
+///   Math. Comp. 30(134) 337-360 (1976),
+///   DOI: [10.2307/2005976](https://www.jstor.org/stable/2005976)
+pub fn primecount_bounds_rh<T: Unsigned>(x: &T) -> (T, T) {
+    let (lower, upper) = primecount_bounds(x);
+    if x.bits_vartime() <= 64 {
+        return (lower, upper);
+    }
+
+    let ln_x = ln(x);
+
+    // Sum the first `N` terms `k! / ln^k x` of the expansion; `term` ends up being `N! / ln^N x`.
+    let num_terms = libm::floor(ln_x / 4.) as u32 - 1;
+    let mut sum = 0.;
+    let mut term = 1.;
+    for k in 1..=num_terms {
+        sum += term;
+        term *= k as f64 / ln_x;
+    }
+
+    let rel_err = FLOAT_SLACK + 2. * term;
+    let abs_err = mul_f64(
+        &x.sqrt_vartime().wrapping_add(&T::one_like(x)),
+        (1. + ln_x / (8. * f64::consts::PI)) * (1. + FLOAT_SLACK),
+        true,
+    );
+
+    let li_lower = Option::from(mul_f64(x, sum / ln_x * (1. - rel_err), false).checked_sub(&abs_err))
+        .unwrap_or_else(|| T::zero_like(x));
+    let li_upper = Option::from(mul_f64(x, sum / ln_x * (1. + rel_err), true).checked_add(&abs_err))
+        .unwrap_or_else(|| max_like(x));
+
+    (lower.max(li_lower), upper.min(li_upper))
+}
+
+/// Estimate the value `x` for which the number of primes below `x` is `n`,
+/// by inverting the approximation of `Li(x)` used in [`estimate_primecount`].
+///
+/// The inverse is found by Newton iteration
+///
+///   $$x_{k+1} = x_k - (Li_{approx}(x_k) - n) \ln x_k,$$
+///
+/// starting from $x_0 = n (\ln n + \ln \ln n)$.
+/// The result is returned once the Newton step is below $2^{-48} x_k$,
+/// so $Li_{approx}(x)$ matches `n` to about the precision of `f64`.
+/// Saturates to the maximum value of `T` if the result does not fit.
+///
+/// # Error considerations
+///
+/// The error relative to $\text{li}^{-1}(n)$ is dominated by the truncation error of $Li_{approx}(x)$
+/// described in [`estimate_primecount`], that is about $24 / \ln^4 x$ in relative terms,
+/// e.g. $\approx 2^{-33}$ for 1024-bit results.
+/// The deviation of $\pi(x)$ from $\text{li}(x)$ adds the theoretical error discussed there;
+/// use [`primecount_bounds`] on the result to get a guaranteed interval for the actual number of primes below it.
+///
+/// Gives meaningful results for `n` above about 100, where $Li_{approx}(x)$ is increasing.
+pub fn estimate_inverse_li<T: Unsigned>(n: &T) -> T {
+    let three = T::from_limb_like(Limb::from(3u32), n);
+
+    let ln_n = ln(n);
+    let ln_ln_n = libm::log(ln_n.max(1.));
+    let mut x = mul_f64(n, ln_n + ln_ln_n, false).max(three.clone());
+
+    for _ in 0..INVERSE_LI_MAX_ITERATIONS {
+        let li_x = estimate_primecount(&x);
+        let below = &li_x < n;
+        let diff = if below {
+            n.wrapping_sub(&li_x)
+        } else {
+            li_x.wrapping_sub(n)
+        };
+        let step = mul_f64(&diff, ln(&x), false);
+
+        x = if below {
+            match Option::from(x.checked_add(&step)) {
+                Some(x) => x,
+                None => return max_like(n),
+            }
+        } else if step < x {
+            x.wrapping_sub(&step).max(three.clone())
+        } else {
+            x.wrapping_shr_vartime(1).max(three.clone())
+        };
+
+        if step.bits_vartime() + INVERSE_LI_CONVERGED_BITS <= x.bits_vartime() {
+            break;
+        }
+    }
+
+    x
+}
+
+/// Estimate the `n`-th prime (1-based, so the first prime is 2).
+///
+/// For $n \le 2048$ the exact value is returned from a precomputed table, and for `n == 0` the result is zero.
+/// Otherwise the estimate is $\text{li}^{-1}(n)$ as calculated by [`estimate_inverse_li`],
+/// and the error considerations of that function apply.
+/// The estimate can be both greater and smaller than the actual prime.
+///
+/// Unconditionally, the `n`-th prime $p_n$ lies within[^Dusart2010]
+///
+///   $$n \left(\ln n + \ln \ln n - 1 + \frac{\ln \ln n - 2.1}{\ln n}\right) \le p_n
+///     \le n \left(\ln n + \ln \ln n - 1 + \frac{\ln \ln n - 2}{\ln n}\right),$$
+///
+/// where the upper bound holds for $n \ge 688383$.
+/// The relative width of this interval is about $0.1 / \ln^2 n$, much larger than the error of the estimate.
+///
+/// [^Dusart2010]: P. Dusart, "Estimates of Some Functions Over Primes without R.H.",
+///   [arXiv:1002.0442](https://arxiv.org/pdf/1002.0442) (2010)
+pub fn estimate_nth_prime<T: Unsigned>(n: &T) -> T {
+    if let Some(n_u64) = to_u64(n) {
+        if n_u64 == 0 {
+            return T::zero_like(n);
+        }
+        if n_u64 == 1 {
+            return T::from_limb_like(Limb::from(2u32), n);
+        }
+        if let Some(prime) = SMALL_PRIMES.get((n_u64 - 2) as usize) {
+            return T::from_limb_like(Limb::from(*prime), n);
+        }
+    }
 
-    let d1 = f64_to_scaled_uint(ln_x);
-    let d2 = f64_to_scaled_uint(ln_x_2);
-    let d3 = f64_to_scaled_uint(ln_x_3);
-    let d4 = f64_to_scaled_uint(ln_x_4);
+    estimate_inverse_li(n)
+}
 
-    let x_wide: Uint<RHS_LIMBS> = x.concat(&Uint::ZERO);
-    let x_wide_scaled = x_wide.wrapping_shl_vartime(total_scale_bits);
+/// Returns `x` as `u64` if it fits.
+fn to_u64<T: Unsigned>(x: &T) -> Option<u64> {
+    if x.bits_vartime() > u64::BITS {
+        return None;
+    }
+    Some(low_u128(x) as u64)
+}
 
-    let term1_scaled = x_wide_scaled.wrapping_div(&d1);
-    let term2_scaled = x_wide_scaled.wrapping_div(&d2);
+/// Returns `value` as an integer with the same precision as `other`.
+fn from_u64_like<T: Unsigned>(value: u64, other: &T) -> T {
+    let lo = T::from_limb_like(Limb::from(value as u32), other);
+    let hi = T::from_limb_like(Limb::from((value >> 32) as u32), other);
+    hi.wrapping_shl_vartime(32) | lo
+}
 
-    // Term 3: (2x << total_scale_bits) / d3  (Factorial 2!)
-    let term3_scaled = x_wide_scaled.wrapping_shl_vartime(1).wrapping_div(&d3);
+/// Returns the largest integer with the same precision as `other`.
+fn max_like<T: Unsigned>(other: &T) -> T {
+    !T::zero_like(other)
+}
 
-    // Term 4: (6x << total_scale_bits) / d4  (Factorial 3!)
-    let six = Uint::<LIMBS>::from(6u64);
-    let x_times_6 = x_wide_scaled.saturating_mul(&six);
-    let term4_scaled = x_times_6.wrapping_div(&d4);
+/// Calculates `x * factor` for `2^-22 <= factor < 2^53`, rounded down, or strictly up if `round_up` is `true`.
+///
+/// Saturates to the maximum value of `T` if the result does not fit.
+fn mul_f64<T: Unsigned>(x: &T, factor: f64, round_up: bool) -> T {
+    // `factor == mantissa * 2^-shift` exactly, with `mantissa` having at most 53 bits.
+    let (fraction, exp) = libm::frexp(factor);
+    let mantissa = (fraction * (1u64 << f64::MANTISSA_DIGITS) as f64) as u64;
+    let shift = f64::MANTISSA_DIGITS as i32 - exp;
+    debug_assert!((1..=75).contains(&shift), "factor is out of range");
+    let shift = shift as u32;
 
-    let sum_scaled = term1_scaled
-        .wrapping_add(&term2_scaled)
-        .wrapping_add(&term3_scaled)
-        .wrapping_add(&term4_scaled);
+    // Split `x` as `x_hi * 2^shift + x_lo`, so that `x_lo * mantissa` fits in `u128`.
+    // Then `x * factor == x_hi * mantissa + x_lo * mantissa / 2^shift`.
+    let x_hi = x.wrapping_shr_vartime(shift);
+    let x_lo = low_u128(x) & ((1u128 << shift) - 1);
+    let lo = ((x_lo * u128::from(mantissa)) >> shift) as u64;
 
-    // Descale by right-shifting
-    let li_x = sum_scaled >> scale_bits;
-    let (lo, hi) = li_x.split();
-    assert_eq!(hi, Uint::ZERO, "De-scaling should leave the high half zero");
-    lo
+    // Detect the overflow by division, since `checked_mul()` is unreliable for `BoxedUint`.
+    let mantissa = from_u64_like(mantissa, x);
+    let hi = x_hi.wrapping_mul(&mantissa);
+    let mantissa: Option<NonZero<T>> = NonZero::new(mantissa).into();
+    let hi_overflow = mantissa.is_some_and(|mantissa| hi.clone() / mantissa != x_hi);
+    let result: Option<T> = if hi_overflow {
+        None
+    } else {
+        hi.checked_add(&from_u64_like(lo, x)).into()
+    };
+    let result = if round_up {
+        result.and_then(|result| result.checked_add(&T::one_like(x)).into())
+    } else {
+        result
+    };
+    result.unwrap_or_else(|| max_like(x))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloc::vec;
-    use alloc::vec::Vec;
-    use crypto_bigint::{U64, U128, U256, U1024};
+    use crypto_bigint::{BoxedUint, U64, U128, U256, U1024, Uint};
+
+    /// Known values for π(x), expressed as a tuple of `(π(x), exponent)`, where the `exponent` is used with base 10.
+    const PI_POWERS_OF_TEN: &[(u128, u32)] = &[
+        // The error is large for small x, so we skip them.
+        // (4, 1),
+        // (25, 2),
+        // (168, 3),
+        (1229, 4),
+        (9592, 5),
+        (78498, 6),
+        (664579, 7),
+        (5761455, 8),
+        (50847534, 9),
+        (455052511, 10),
+        (4118054813, 11),
+        (37607912018, 12),
+        (346065536839, 13),
+        (3204941750802, 14),
+        (29844570422669, 15),
+        (279238341033925, 16),
+        (2623557157654233, 17),
+        (24739954287740860, 18),
+        (234057667276344607, 19),
+        (2220819602560918840, 20),
+        (21127269486018731928, 21),
+        (201467286689315906290, 22),
+        (1925320391606803968923, 23),
+        (18435599767349200867866, 24),
+        (176846309399143769411680, 25),
+        (1699246750872437141327603, 26),
+        (16352460426841680446427399, 27),
+        (157589269275973410412739598, 28),
+        (1520698109714272166094258063, 29),
+    ];
 
     #[test]
     fn pi_x_2_500() {
@@ -223,40 +463,7 @@ mod tests {
 
     #[test]
     fn pi_x_estimates_for_known_values() {
-        // List of known values for π(x), expressed as a tuple of `(π(x), exponent)`, where the `exponent` is used with base 10.
-        let pi_xs: Vec<(u128, u32)> = vec![
-            // The error is large for small x, so we skip them.
-            // (4, 1),
-            // (25, 2),
-            // (168, 3),
-            (1229, 4),
-            (9592, 5),
-            (78498, 6),
-            (664579, 7),
-            (5761455, 8),
-            (50847534, 9),
-            (455052511, 10),
-            (4118054813, 11),
-            (37607912018, 12),
-            (346065536839, 13),
-            (3204941750802, 14),
-            (29844570422669, 15),
-            (279238341033925, 16),
-            (2623557157654233, 17),
-            (24739954287740860, 18),
-            (234057667276344607, 19),
-            (2220819602560918840, 20),
-            (21127269486018731928, 21),
-            (201467286689315906290, 22),
-            (1925320391606803968923, 23),
-            (18435599767349200867866, 24),
-            (176846309399143769411680, 25),
-            (1699246750872437141327603, 26),
-            (16352460426841680446427399, 27),
-            (157589269275973410412739598, 28),
-            (1520698109714272166094258063, 29),
-        ];
-        for (pi_x, exponent) in pi_xs.iter() {
+        for (pi_x, exponent) in PI_POWERS_OF_TEN.iter() {
             let pi_x_wide = U256::from_u128(*pi_x);
             let n = U256::from_u128(10u128.pow(*exponent));
             let estimate = estimate_primecount(&n);
@@ -275,6 +482,129 @@ mod tests {
         }
     }
 
+    #[test]
+    fn bounds_exact_for_small_x() {
+        for x in (0..DUSART_LOWER_BOUND_MIN).step_by(997) {
+            let count = U64::from(primecount_exact(x));
+            assert_eq!(primecount_bounds(&U64::from(x)), (count, count));
+        }
+    }
+
+    #[test]
+    fn bounds_contain_exact_values() {
+        for x in (DUSART_LOWER_BOUND_MIN..10_000_000).step_by(99991) {
+            let count = U64::from(primecount_exact(x));
+            let (lower, upper) = primecount_bounds(&U64::from(x));
+            assert!(lower <= count && count <= upper, "x = {x}");
+        }
+    }
+
+    #[test]
+    fn bounds_contain_known_values() {
+        for (pi_x, exponent) in PI_POWERS_OF_TEN.iter() {
+            let pi_x = U128::from_u128(*pi_x);
+            let x = U128::from_u128(10u128.pow(*exponent));
+
+            let (lower, upper) = primecount_bounds(&x);
+            assert!(
+                lower <= pi_x && pi_x <= upper,
+                "10^{exponent}: {lower} <= {pi_x} <= {upper}"
+            );
+
+            let (lower_rh, upper_rh) = primecount_bounds_rh(&x);
+            assert!(lower <= lower_rh && upper_rh <= upper);
+            assert!(
+                lower_rh <= pi_x && pi_x <= upper_rh,
+                "10^{exponent}: {lower_rh} <= {pi_x} <= {upper_rh}"
+            );
+        }
+    }
+
+    #[test]
+    fn bounds_large() {
+        let x = U1024::MAX;
+        let estimate = estimate_primecount(&x);
+
+        let (lower, upper) = primecount_bounds(&x);
+        assert!(lower <= estimate && estimate <= upper);
+        assert!(estimate.bits_vartime() - (upper - lower).bits_vartime() >= 18);
+
+        let (lower_rh, upper_rh) = primecount_bounds_rh(&x);
+        assert!(lower <= lower_rh && upper_rh <= upper);
+        assert!(estimate.bits_vartime() - (upper_rh - lower_rh).bits_vartime() >= 37);
+    }
+
+    #[test]
+    fn boxed_uint() {
+        let x = U1024::MAX >> 3;
+        let x_boxed = BoxedUint::from(&x);
+
+        assert_eq!(estimate_primecount(&x_boxed), BoxedUint::from(estimate_primecount(&x)));
+
+        let (lower, upper) = primecount_bounds(&x);
+        assert_eq!(
+            primecount_bounds(&x_boxed),
+            (BoxedUint::from(lower), BoxedUint::from(upper))
+        );
+
+        let (lower, upper) = primecount_bounds(&U64::from(1000u32));
+        assert_eq!(
+            primecount_bounds(&BoxedUint::from(1000u32)),
+            (BoxedUint::from(lower), BoxedUint::from(upper))
+        );
+    }
+
+    #[test]
+    fn nth_prime_small() {
+        assert_eq!(estimate_nth_prime(&U64::ZERO), U64::ZERO);
+        assert_eq!(estimate_nth_prime(&U64::ONE), U64::from(2u32));
+        assert_eq!(estimate_nth_prime(&U64::from(2u32)), U64::from(3u32));
+        assert_eq!(estimate_nth_prime(&U64::from(2048u32)), U64::from(17863u32));
+    }
+
+    #[test]
+    fn nth_prime_known_values() {
+        // `(p_n, exponent)` where `n = 10^exponent`
+        let nth_primes: &[(u128, u32)] = &[
+            (104729, 4),
+            (1299709, 5),
+            (15485863, 6),
+            (179424673, 7),
+            (2038074743, 8),
+            (22801763489, 9),
+            (252097800623, 10),
+            (2760727302517, 11),
+            (29996224275833, 12),
+            (323780508946331, 13),
+            (3475385758524527, 14),
+            (37124508045065437, 15),
+            (394906913903735329, 16),
+            (4185296581467695669, 17),
+            (44211790234832169331, 18),
+        ];
+        for (p_n, exponent) in nth_primes.iter() {
+            let n = U128::from_u128(10u128.pow(*exponent));
+            let estimate = uint_to_u128(&estimate_nth_prime(&n));
+            let error = estimate.abs_diff(*p_n) as f64 / *p_n as f64;
+            // Allow twice the truncation error of the `Li(x)` approximation.
+            let max_error = 48. / libm::pow(libm::log(*p_n as f64), 4.);
+            assert!(error < max_error, "10^{exponent}: {p_n} vs {estimate}, err: {error:e}");
+        }
+    }
+
+    #[test]
+    fn inverse_li_roundtrip() {
+        let n = U1024::MAX >> 12;
+        let x = estimate_inverse_li(&n);
+        assert_bit_difference(estimate_primecount(&x), n, 45);
+
+        let n_boxed = BoxedUint::from(&n);
+        assert_eq!(estimate_inverse_li(&n_boxed), BoxedUint::from(&x));
+
+        let n = U1024::MAX >> 3;
+        assert_eq!(estimate_inverse_li(&n), U1024::MAX);
+    }
+
     fn uint_to_u128<const LIMBS: usize>(x: &Uint<LIMBS>) -> u128 {
         let limbs = x.as_limbs();
         #[cfg(target_pointer_width = "32")]
@@ -0,0 +1,420 @@
+//! Sieving through the numbers in an arbitrary interval.
+
+use core::num::NonZeroU32;
+
+use crypto_bigint::{NonZero as CTNonZero, RandomBits, RandomMod, Unsigned};
+use rand_core::CryptoRng;
+
+use super::{
+    precomputed::LAST_SMALL_PRIME,
+    sieve::{DecreasingSieve, SieveFactory, SmallFactorsSieve},
+};
+use crate::{error::Error, presets::Flavor};
+
+/// An iterator returning the results of a [`SmallFactorsSieve`] that are not greater than a given bound,
+/// or the numbers in an interval in decreasing order (see [`new_decreasing`](`Self::new_decreasing`)).
+///
+/// Since a [`SmallFactorsSieve`] only uses the small primes below its starting point,
+/// an interval starting at a small number is split into segments, each at most about twice as long
+/// as the previous one, with a new sieve (using more small primes) created for each segment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeSieve<T: Unsigned> {
+    direction: Direction<T>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Direction<T: Unsigned> {
+    Increasing {
+        sieve: SmallFactorsSieve<T>,
+        max_bit_length: NonZeroU32,
+        flavor: Flavor,
+        segment_max: T,
+        max: T,
+        exhausted: bool,
+    },
+    Decreasing(DecreasingSieve<T>),
+}
+
+impl<T> RangeSieve<T>
+where
+    T: Unsigned,
+{
+    /// Creates a new sieve, iterating from `start` and until `max` (inclusive),
+    /// producing numbers that are not non-trivial multiples of a list of small primes.
+    ///
+    /// See [`SmallFactorsSieve::with_flavor`] for the details on the adjustment of `start` and the applied filters
+    /// depending on the `flavor`.
+    ///
+    /// The sieve produces nothing if `max < start`.
+    pub fn new(start: T, max: T, flavor: Flavor) -> Result<Self, Error> {
+        let exhausted = max < start || max.bits_vartime() == 0;
+        let max_bit_length = NonZeroU32::new(max.bits_vartime()).unwrap_or(NonZeroU32::MIN);
+        // Bring `start` to the precision of `max`, if it is larger (only matters for boxed integers).
+        let start = T::zero_like(&max).wrapping_add(&start);
+        Ok(Self {
+            direction: Direction::Increasing {
+                segment_max: segment_max(&start, &max),
+                sieve: SmallFactorsSieve::with_flavor(start, max_bit_length, flavor)?,
+                max_bit_length,
+                flavor,
+                max,
+                exhausted,
+            },
+        })
+    }
+
+    /// Creates a new sieve, iterating from `max` down to `min` (inclusive),
+    /// producing numbers that are not non-trivial multiples of a list of small primes.
+    ///
+    /// The numbers are taken from the same residue classes as the ones produced by [`new`](`Self::new`)
+    /// for the given `flavor`.
+    ///
+    /// The sieve produces nothing if `max < min`.
+    pub fn new_decreasing(min: T, max: T, flavor: Flavor) -> Self {
+        Self {
+            direction: Direction::Decreasing(DecreasingSieve::new(min, max, flavor)),
+        }
+    }
+
+    fn next(&mut self) -> Option<T> {
+        match &mut self.direction {
+            Direction::Increasing {
+                sieve,
+                max_bit_length,
+                flavor,
+                segment_max,
+                max,
+                exhausted,
+            } => loop {
+                if *exhausted {
+                    return None;
+                }
+                match sieve.next() {
+                    Some(num) if &num <= segment_max => return Some(num),
+                    // Start the next segment; the sieve for it will use more small primes.
+                    Some(_) if segment_max < max => {
+                        let start = segment_max.wrapping_add(&T::one_like(segment_max));
+                        *segment_max = self::segment_max(&start, max);
+                        *sieve = SmallFactorsSieve::with_flavor(start, *max_bit_length, *flavor)
+                            .expect("the parameters were checked when the first sieve was created");
+                    }
+                    _ => *exhausted = true,
+                }
+            },
+            Direction::Decreasing(sieve) => sieve.next(),
+        }
+    }
+}
+
+/// Returns the end of the segment of `[start, max]` that is sieved by a single [`SmallFactorsSieve`].
+///
+/// Every composite number in `[start, 2 * start + 3]` has a factor smaller than `start` (if `start >= 4`),
+/// so the sieve starting at `start` catches all of them; once `start` is large enough for all the small primes
+/// to be used (for both `n` and `n / 2`), the segment extends to `max`.
+fn segment_max<T: Unsigned>(start: &T, max: &T) -> T {
+    let limit = T::from_limb_like((2 * u32::from(LAST_SMALL_PRIME)).into(), max);
+    if start >= &limit {
+        return max.clone();
+    }
+    // `start` is small, so this does not overflow.
+    let end = start
+        .wrapping_shl_vartime(1)
+        .wrapping_add(&T::from_limb_like(3u32.into(), max));
+    if &end < max { end } else { max.clone() }
+}
+
+impl<T> Iterator for RangeSieve<T>
+where
+    T: Unsigned,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Self::next(self)
+    }
+}
+
+/// A sieve factory covering the interval `[min, max]`.
+///
+/// The first sieve starts at a uniformly random point in the interval and goes until `max`,
+/// and the second one wraps around, starting at `min` and stopping right before the first starting point.
+/// After that, the factory stops producing sieves.
+#[derive(Debug, Clone)]
+pub struct RangeSieveFactory<T> {
+    flavor: Flavor,
+    min: T,
+    max: T,
+    start: Option<T>,
+    wrapped: bool,
+}
+
+impl<T> RangeSieveFactory<T>
+where
+    T: Unsigned + RandomBits + RandomMod,
+{
+    /// Creates a factory that produces sieves returning numbers in the interval `[min, max]`
+    /// that are not divisible by a number of small factors.
+    ///
+    /// Depending on the requested `flavor`, additional filters may be applied.
+    ///
+    /// Returns an error if `min > max`.
+    pub fn new(flavor: Flavor, min: T, max: T) -> Result<Self, Error> {
+        if min > max {
+            return Err(Error::InvalidParameter { name: "min" });
+        }
+        // Bring everything to the same precision (only matters for boxed integers).
+        let zero = T::zero_like(&min).wrapping_add(&T::zero_like(&max));
+        Ok(Self {
+            flavor,
+            min: zero.wrapping_add(&min),
+            max: zero.wrapping_add(&max),
+            start: None,
+            wrapped: false,
+        })
+    }
+}
+
+impl<T> SieveFactory for RangeSieveFactory<T>
+where
+    T: Unsigned + RandomBits + RandomMod,
+{
+    type Item = T;
+    type Sieve = RangeSieve<T>;
+    fn make_sieve<R>(
+        &mut self,
+        rng: &mut R,
+        _previous_sieve: Option<&Self::Sieve>,
+    ) -> Result<Option<Self::Sieve>, Error>
+    where
+        R: CryptoRng + ?Sized,
+    {
+        match &self.start {
+            None => {
+                // A uniformly random point in `[min, max]`.
+                let width = self.max.wrapping_sub(&self.min);
+                let offset = match width.checked_add(&T::one_like(&width)).into_option() {
+                    Some(size) => T::random_mod(rng, &CTNonZero::new(size).expect("the size is non-zero")),
+                    // The interval covers the whole range of `T`.
+                    None => T::try_random_bits(rng, width.bits_precision())
+                        .expect("the requested bit length is the precision of the type"),
+                };
+                let start = self.min.wrapping_add(&offset);
+                self.start = Some(start.clone());
+                Ok(Some(RangeSieve::new(start, self.max.clone(), self.flavor)?))
+            }
+            Some(start) => {
+                if self.wrapped || start == &self.min {
+                    return Ok(None);
+                }
+                self.wrapped = true;
+                let max = start.wrapping_sub(&T::one_like(start));
+                Ok(Some(RangeSieve::new(self.min.clone(), max, self.flavor)?))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crypto_bigint::{BoxedUint, U64};
+    use num_prime::nt_funcs::is_prime64;
+
+    use super::{RangeSieve, RangeSieveFactory};
+    use crate::{
+        Error, Flavor,
+        hazmat::{SieveFactory, precomputed::SMALL_PRIMES},
+    };
+
+    fn collect_all(factory: &mut RangeSieveFactory<U64>) -> Vec<u64> {
+        let mut rng = rand::rng();
+        let mut result = Vec::new();
+        let mut previous = None;
+        while let Some(sieve) = factory.make_sieve(&mut rng, previous.as_ref()).unwrap() {
+            result.extend(sieve.clone().map(u64::from));
+            previous = Some(sieve);
+        }
+        result.sort();
+        result
+    }
+
+    #[test]
+    fn covers_the_range() {
+        for _ in 0..10 {
+            let mut factory = RangeSieveFactory::new(Flavor::Any, U64::from(1000u32), U64::from(1100u32)).unwrap();
+            let result = collect_all(&mut factory);
+
+            // All the primes in the interval are there.
+            let primes = (1000..=1100).filter(|x| is_prime64(*x)).collect::<Vec<_>>();
+            assert!(primes.iter().all(|p| result.contains(p)));
+            // Nothing outside of the interval.
+            assert!(result.iter().all(|x| (1000..=1100).contains(x)));
+            // No duplicates.
+            assert!(result.windows(2).all(|pair| pair[0] != pair[1]));
+        }
+    }
+
+    #[test]
+    fn single_element() {
+        let mut factory = RangeSieveFactory::new(Flavor::Any, U64::from(101u32), U64::from(101u32)).unwrap();
+        assert_eq!(collect_all(&mut factory), [101]);
+
+        let mut factory = RangeSieveFactory::new(Flavor::Any, U64::from(2u32), U64::from(2u32)).unwrap();
+        assert_eq!(collect_all(&mut factory), [2]);
+
+        let mut factory = RangeSieveFactory::new(Flavor::Any, U64::from(0u32), U64::from(1u32)).unwrap();
+        assert_eq!(collect_all(&mut factory), []);
+    }
+
+    #[test]
+    fn flavors() {
+        let mut factory = RangeSieveFactory::new(Flavor::Safe, U64::from(1000u32), U64::from(1100u32)).unwrap();
+        let result = collect_all(&mut factory);
+        assert!(result.contains(&1019));
+        assert!(result.iter().all(|x| x % 4 == 3));
+
+        let mut factory = RangeSieveFactory::new(Flavor::Blum, U64::from(1000u32), U64::from(1100u32)).unwrap();
+        let result = collect_all(&mut factory);
+        assert!(result.contains(&1019));
+        assert!(result.contains(&1031));
+        assert!(result.iter().all(|x| x % 4 == 3));
+    }
+
+    #[test]
+    fn sieve_bounds() {
+        let max_prime = SMALL_PRIMES[SMALL_PRIMES.len() - 1] as u64;
+        let start = 1u64 << 40;
+        let max = start + 10000;
+        let sieve = RangeSieve::new(U64::from(start), U64::from(max), Flavor::Any).unwrap();
+        let result = sieve.map(u64::from).collect::<Vec<_>>();
+        assert!(!result.is_empty());
+        for num in result {
+            assert!((start..=max).contains(&num));
+            assert!(
+                SMALL_PRIMES
+                    .iter()
+                    .all(|p| num % *p as u64 != 0 || (*p as u64) > max_prime)
+            );
+        }
+
+        let mut sieve = RangeSieve::new(U64::from(200u32), U64::from(100u32), Flavor::Any).unwrap();
+        assert!(sieve.next().is_none());
+    }
+
+    #[test]
+    fn small_start() {
+        // A sieve starting at a small number still uses the small primes as it goes up,
+        // so (since the small primes cover all the possible factors of these numbers)
+        // only the primes of the requested flavor are produced.
+        for flavor in [Flavor::Any, Flavor::Safe, Flavor::Blum] {
+            for (min, max) in [(0, 2), (0, 100), (2, 50_000), (5, 1000), (30_000, 40_000)] {
+                let result = RangeSieve::new(U64::from(min), U64::from(max), flavor)
+                    .unwrap()
+                    .map(u64::from)
+                    .collect::<Vec<_>>();
+                let reference = (min..=max)
+                    .filter(|num| match flavor {
+                        Flavor::Any => is_prime64(*num),
+                        Flavor::Safe => is_prime64(*num) && is_prime64(*num >> 1),
+                        Flavor::Blum => is_prime64(*num) && num % 4 == 3,
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(result, reference, "{flavor:?}, [{min}, {max}]");
+            }
+        }
+    }
+
+    fn check_decreasing(flavor: Flavor, min: u64, max: u64) {
+        let result = RangeSieve::new_decreasing(U64::from(min), U64::from(max), flavor)
+            .map(u64::from)
+            .collect::<Vec<_>>();
+
+        // Strictly decreasing and within the interval.
+        assert!(result.windows(2).all(|pair| pair[0] > pair[1]));
+        assert!(result.iter().all(|x| (min..=max).contains(x)));
+
+        // All the primes of the given flavor in the interval are there,
+        // and the rest of the numbers are not multiples of the small primes below them.
+        for num in min..=max {
+            let expected = match flavor {
+                Flavor::Any => is_prime64(num),
+                Flavor::Safe => is_prime64(num) && is_prime64(num >> 1),
+                Flavor::Blum => is_prime64(num) && num % 4 == 3,
+            };
+            if expected {
+                assert!(result.contains(&num), "{flavor:?}: missing {num}");
+            }
+        }
+        for num in &result {
+            match flavor {
+                Flavor::Any => assert!(*num == 2 || num % 2 == 1),
+                Flavor::Safe => assert!(*num == 5 || num % 4 == 3),
+                Flavor::Blum => assert_eq!(num % 4, 3),
+            }
+            let max_prime = (*num / 2).min(SMALL_PRIMES[SMALL_PRIMES.len() - 1] as u64);
+            assert!(
+                SMALL_PRIMES
+                    .iter()
+                    .map(|p| *p as u64)
+                    .take_while(|p| *p <= max_prime)
+                    .all(|p| num % p != 0),
+                "{flavor:?}: {num} has a small factor"
+            );
+        }
+    }
+
+    #[test]
+    fn decreasing() {
+        for flavor in [Flavor::Any, Flavor::Safe, Flavor::Blum] {
+            for (min, max) in [
+                (0, 0),
+                (0, 2),
+                (2, 2),
+                (3, 3),
+                (5, 5),
+                (0, 100),
+                (4, 99),
+                (0, 50_000),
+                (1000, 1100),
+                (1 << 40, (1 << 40) + 10000),
+                (u64::MAX - 10000, u64::MAX),
+            ] {
+                check_decreasing(flavor, min, max);
+            }
+        }
+
+        let mut sieve = RangeSieve::new_decreasing(U64::from(200u32), U64::from(100u32), Flavor::Any);
+        assert!(sieve.next().is_none());
+    }
+
+    #[test]
+    fn decreasing_boxed() {
+        let max = BoxedUint::one_with_precision(128).wrapping_shl_vartime(100);
+        let sieve = RangeSieve::new_decreasing(BoxedUint::from(1000u32), max.clone(), Flavor::Any);
+        let result = sieve.take(10).collect::<Vec<_>>();
+        assert_eq!(result.len(), 10);
+        assert!(result.windows(2).all(|pair| pair[0] > pair[1]));
+        assert!(result[0] < max);
+    }
+
+    #[test]
+    fn boxed() {
+        let mut rng = rand::rng();
+        let mut factory =
+            RangeSieveFactory::new(Flavor::Any, BoxedUint::from(1000u32), BoxedUint::from(1u64 << 50)).unwrap();
+        let sieve = factory.make_sieve(&mut rng, None).unwrap().unwrap();
+        for num in sieve.take(10) {
+            assert!(num >= BoxedUint::from(1000u32));
+            assert!(num <= BoxedUint::from(1u64 << 50));
+        }
+    }
+
+    #[test]
+    fn invalid_range() {
+        assert_eq!(
+            RangeSieveFactory::new(Flavor::Any, U64::from(2u32), U64::ONE).unwrap_err(),
+            Error::InvalidParameter { name: "min" }
+        );
+    }
+}
@@ -5,10 +5,12 @@ use alloc::{vec, vec::Vec};
 use core::marker::PhantomData;
 use core::num::{NonZero, NonZeroU32};
 
-use crypto_bigint::{Odd, RandomBits, RandomBitsError, Unsigned};
-use rand_core::CryptoRng;
+use crypto_bigint::{NonZero as CTNonZero, Odd, RandomBits, RandomBitsError, Unsigned};
+use rand_core::{CryptoRng, RngCore};
 
 use super::precomputed::{LAST_SMALL_PRIME, RECIPROCALS, SMALL_PRIMES, SmallPrime};
+#[cfg(feature = "zeroize")]
+use super::wipe::wipe_integer;
 use crate::{error::Error, presets::Flavor};
 
 /// Decide how prime candidates are manipulated by setting certain bits before primality testing,
@@ -40,19 +42,7 @@ where
 {
     let bit_length = bit_length.get();
 
-    let mut random = T::try_random_bits(rng, bit_length).map_err(|err| match err {
-        RandomBitsError::RandCore(_) => unreachable!("`rng` impls `CryptoRng` and therefore is infallible"),
-        RandomBitsError::BitsPrecisionMismatch { .. } => {
-            unreachable!("we are not requesting a specific `bits_precision`")
-        }
-        RandomBitsError::BitLengthTooLarge {
-            bit_length,
-            bits_precision,
-        } => Error::BitLengthTooLarge {
-            bit_length,
-            bits_precision,
-        },
-    })?;
+    let mut random = T::try_random_bits(rng, bit_length).map_err(convert_random_bits_error)?;
 
     // Make it odd
     // `bit_length` is non-zero, so the 0-th bit exists.
@@ -77,6 +67,65 @@ where
     Ok(Odd::new(random).expect("the number is odd by construction"))
 }
 
+/// Returns a zero with the precision sufficient to hold a number of `bit_length` bits.
+///
+/// Returns an error variant if `bit_length` is greater than the maximum allowed for `T`
+/// (applies to fixed-length types).
+pub(crate) fn zero_with_bit_length<T>(bit_length: u32) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits,
+{
+    // `RandomBits` is the only generic way to request an integer of a certain precision,
+    // so we are feeding it an RNG that only produces zeros.
+    struct ZeroRng;
+
+    impl RngCore for ZeroRng {
+        fn next_u32(&mut self) -> u32 {
+            0
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            0
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            dst.fill(0)
+        }
+    }
+
+    T::try_random_bits(&mut ZeroRng, bit_length).map_err(convert_random_bits_error)
+}
+
+/// Returns a copy of `num` with the precision sufficient to hold a number of `bit_length` bits.
+///
+/// Only matters for boxed integers: the results of arithmetic operations inherit the precision of the operands,
+/// so the inputs of a generation procedure have to be brought to the precision of its result first.
+///
+/// Returns an error variant if `bit_length` is greater than the maximum allowed for `T`
+/// (applies to fixed-length types).
+pub(crate) fn to_working_precision<T>(num: &T, bit_length: u32) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits,
+{
+    Ok(zero_with_bit_length::<T>(bit_length)?.wrapping_add(num))
+}
+
+fn convert_random_bits_error<E>(err: RandomBitsError<E>) -> Error {
+    match err {
+        RandomBitsError::RandCore(_) => unreachable!("`rng` impls `CryptoRng` and therefore is infallible"),
+        RandomBitsError::BitsPrecisionMismatch { .. } => {
+            unreachable!("we are not requesting a specific `bits_precision`")
+        }
+        RandomBitsError::BitLengthTooLarge {
+            bit_length,
+            bits_precision,
+        } => Error::BitLengthTooLarge {
+            bit_length,
+            bits_precision,
+        },
+    }
+}
+
 // The type we use to calculate incremental residues.
 // Should be >= `SmallPrime` in size.
 type Residue = u32;
@@ -87,6 +136,8 @@ const INCR_LIMIT: Residue = Residue::MAX - LAST_SMALL_PRIME as Residue + 1;
 
 /// An iterator returning numbers with up to and including given bit length,
 /// starting from a given number, that are not multiples of the first 2048 small primes.
+///
+/// If the `zeroize` feature is enabled, the secret state is wiped on drop (see the `Zeroize` implementation).
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SmallFactorsSieve<T: Unsigned> {
     // Instead of dividing a big integer by small primes every time (which is slow),
@@ -95,6 +146,7 @@ pub struct SmallFactorsSieve<T: Unsigned> {
     base: T,
     incr: Residue,
     incr_limit: Residue,
+    incr_step: Residue,
     safe_primes: bool,
     residues: Vec<SmallPrime>,
     max_bit_length: u32,
@@ -111,14 +163,30 @@ where
     /// bits, producing numbers that are not non-trivial multiples of a list of small primes in the
     /// range `[2, start)` (`safe_primes = false`) or `[2, start/2)` (`safe_primes = true`).
     ///
-    /// Note that `start` is adjusted to `2`, or the next `1 mod 2` number (`safe_primes = false`);
-    /// and `5`, or `3 mod 4` number (`safe_primes = true`).
+    /// Same as [`with_flavor`](`Self::with_flavor`) with [`Flavor::Any`] (`safe_primes = false`)
+    /// or [`Flavor::Safe`] (`safe_primes = true`).
+    pub fn new(start: T, max_bit_length: NonZeroU32, safe_primes: bool) -> Result<Self, Error> {
+        let flavor = if safe_primes { Flavor::Safe } else { Flavor::Any };
+        Self::with_flavor(start, max_bit_length, flavor)
+    }
+
+    /// Creates a new sieve, iterating from `start` and until the last number with `max_bit_length`
+    /// bits, producing numbers that are not non-trivial multiples of a list of small primes in the
+    /// range `[2, start)` (`flavor` is [`Flavor::Any`] or [`Flavor::Blum`])
+    /// or `[2, start/2)` (`flavor` is [`Flavor::Safe`]).
+    ///
+    /// Note that `start` is adjusted to `2`, or the next `1 mod 2` number ([`Flavor::Any`]);
+    /// `5`, or the next `3 mod 4` number ([`Flavor::Safe`]);
+    /// and the next `3 mod 4` number ([`Flavor::Blum`]).
     ///
     /// Panics if `max_bit_length` greater than the precision of `start`.
     ///
-    /// If `safe_primes` is `true`, both the returned `n` and `n/2` are sieved.
-    pub fn new(start: T, max_bit_length: NonZeroU32, safe_primes: bool) -> Result<Self, Error> {
+    /// If `flavor` is [`Flavor::Safe`], both the returned `n` and `n/2` are sieved.
+    /// If `flavor` is [`Flavor::Blum`], only the numbers equal to 3 modulo 4 are visited.
+    pub fn with_flavor(start: T, max_bit_length: NonZeroU32, flavor: Flavor) -> Result<Self, Error> {
         let max_bit_length = max_bit_length.get();
+        let safe_primes = flavor == Flavor::Safe;
+        let blum_primes = flavor == Flavor::Blum;
 
         if max_bit_length > start.bits_precision() {
             return Err(Error::BitLengthTooLarge {
@@ -135,13 +203,14 @@ where
             (max_bit_length, start)
         };
 
-        // This is easier than making all the methods generic enough to handle these corner cases.
-        let produces_nothing = max_bit_length < start.bits_vartime() || max_bit_length < 2;
-
         // Add the exception to the produced candidates - the only one that doesn't fit
         // the general pattern of incrementing the base by 2.
         let mut starts_from_exception = false;
-        if start <= T::from(2u32) {
+        if blum_primes {
+            // 3 is the smallest Blum prime, and there are no exceptions to the pattern.
+            // Adjust the start so that we hit `3 mod 4` numbers when incrementing it by 4.
+            start |= T::from(3u32);
+        } else if start <= T::from(2u32) {
             starts_from_exception = true;
             start = T::from(3u32);
         } else {
@@ -149,6 +218,10 @@ where
             start |= T::one();
         }
 
+        // This is easier than making all the methods generic enough to handle these corner cases.
+        // Note that this must be checked after `start` is adjusted, since it may gain a bit in the process.
+        let produces_nothing = max_bit_length < start.bits_vartime() || max_bit_length < 2;
+
         // Only calculate residues by primes up to and not including `start`, because when we only
         // have the resiude, we cannot distinguish between a prime itself and a multiple of that
         // prime.
@@ -165,6 +238,7 @@ where
             base: start,
             incr: 0, // This will ensure that `update_residues()` is called right away.
             incr_limit: 0,
+            incr_step: if blum_primes { 4 } else { 2 },
             safe_primes,
             residues: vec![0; residues_len],
             max_bit_length,
@@ -187,10 +261,16 @@ where
         // Should not overflow since `incr` is never greater than `incr_limit`,
         // and the latter is chosen such that it doesn't overflow when added to `base`
         // (see the rest of this method).
-        self.base = self
+        let new_base = self
             .base
             .checked_add(&self.incr.into())
             .expect("Does not overflow by construction");
+        #[cfg(feature = "zeroize")]
+        wipe_integer(&mut core::mem::replace(&mut self.base, new_base));
+        #[cfg(not(feature = "zeroize"))]
+        {
+            self.base = new_base;
+        }
 
         self.incr = 0;
 
@@ -261,7 +341,7 @@ where
             }
         };
 
-        self.incr += 2;
+        self.incr += self.incr_step;
         result
     }
 
@@ -299,6 +379,349 @@ where
     }
 }
 
+/// Wipes the current base (from which the secret candidates are derived),
+/// the increment, and the residues of the base modulo the small primes.
+/// The parameters of the sieve are left intact, but it will not produce any more numbers.
+#[cfg(feature = "zeroize")]
+impl<T> zeroize::Zeroize for SmallFactorsSieve<T>
+where
+    T: Unsigned,
+{
+    fn zeroize(&mut self) {
+        wipe_integer(&mut self.base);
+        self.incr.zeroize();
+        self.residues.zeroize();
+        self.produces_nothing = true;
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T> Drop for SmallFactorsSieve<T>
+where
+    T: Unsigned,
+{
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T> zeroize::ZeroizeOnDrop for SmallFactorsSieve<T> where T: Unsigned {}
+
+// The maximum number of steps in a round of `ProgressionSieve`.
+// The residues of the candidates are calculated as `residue + incr * step_residue` in `u64`,
+// which does not overflow for any `u32` increment, and `incr` itself is bumped past the limit once.
+const PROGRESSION_INCR_LIMIT: Residue = Residue::MAX - 1;
+
+/// The residue bookkeeping shared by the sieves over arithmetic progressions:
+/// visits the numbers `first, first ± step, first ± 2 * step, ...` up to and including `end`,
+/// keeping track of their residues modulo the small primes.
+///
+/// The sieves only decide which residues make a candidate composite, see [`next_by`](`Self::next_by`).
+///
+/// If the `zeroize` feature is enabled, the current position and the residues are wiped on drop.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ProgressionSieve<T: Unsigned> {
+    // Instead of dividing a big integer by small primes every time (which is slow),
+    // we keep a "base" and a small increment separately,
+    // so that we can only calculate the residues of the increment.
+    // The candidates of the current round are `base + incr * step` (or `base - incr * step`).
+    base: T,
+    step: CTNonZero<T>,
+    end: T,
+    decreasing: bool,
+    incr: Residue,
+    incr_limit: Residue,
+    residues: Vec<SmallPrime>,
+    // The residues of `step` (or `-step`, if `decreasing` is set) modulo all the small primes.
+    step_residues: Vec<SmallPrime>,
+    round_started: bool,
+    last_round: bool,
+    exhausted: bool,
+}
+
+impl<T> ProgressionSieve<T>
+where
+    T: Unsigned,
+{
+    /// Creates a progression going up from `first` to `max` (inclusive).
+    ///
+    /// Panics if `step` is zero.
+    pub fn increasing(first: T, max: T, step: T) -> Self {
+        Self::new(first, max, step, false)
+    }
+
+    /// Creates a progression going down from `first` to `min` (inclusive).
+    ///
+    /// Each round only goes down to about a half of its starting point,
+    /// so that all the small primes below that can be used for sieving.
+    ///
+    /// Panics if `step` is zero.
+    pub fn decreasing(first: T, min: T, step: T) -> Self {
+        Self::new(first, min, step, true)
+    }
+
+    fn new(first: T, end: T, step: T, decreasing: bool) -> Self {
+        // Bring everything to the same precision (only matters for boxed integers).
+        let zero = T::zero_like(&first)
+            .wrapping_add(&T::zero_like(&end))
+            .wrapping_add(&T::zero_like(&step));
+        let first = zero.wrapping_add(&first);
+        let end = zero.wrapping_add(&end);
+        let step = CTNonZero::new(zero.wrapping_add(&step)).expect("the step should be non-zero");
+
+        let step_residues = RECIPROCALS
+            .iter()
+            .zip(SMALL_PRIMES.iter())
+            .map(|(rec, d)| {
+                let rem = step.as_ref().rem_limb_with_reciprocal(rec).0 as SmallPrime;
+                if decreasing { (d - rem) % d } else { rem }
+            })
+            .collect();
+
+        let exhausted = if decreasing { first < end } else { first > end };
+
+        Self {
+            base: first,
+            step,
+            end,
+            decreasing,
+            incr: 0,
+            incr_limit: 0,
+            residues: Vec::new(),
+            step_residues,
+            round_started: false,
+            last_round: false,
+            exhausted,
+        }
+    }
+
+    // Returns `base + incr * step` (or `base - incr * step`).
+    fn current(&self) -> T {
+        let shift = self
+            .step
+            .as_ref()
+            .wrapping_mul(&T::from_limb_like(self.incr.into(), &self.base));
+        if self.decreasing {
+            self.base.wrapping_sub(&shift)
+        } else {
+            self.base.wrapping_add(&shift)
+        }
+    }
+
+    fn update_residues(&mut self) -> bool {
+        if self.round_started && self.incr <= self.incr_limit {
+            return true;
+        }
+
+        if self.last_round {
+            return false;
+        }
+
+        // Set the new base.
+        // Does not overflow: if this is not the last round, there are more than `incr_limit` steps left,
+        // and `incr` is `incr_limit + 1` at this point.
+        if self.round_started {
+            let new_base = self.current();
+            #[cfg(feature = "zeroize")]
+            wipe_integer(&mut core::mem::replace(&mut self.base, new_base));
+            #[cfg(not(feature = "zeroize"))]
+            {
+                self.base = new_base;
+            }
+        }
+        self.round_started = true;
+        self.incr = 0;
+
+        // Find the increment limit.
+        let distance = if self.decreasing {
+            self.base.wrapping_sub(&self.end)
+        } else {
+            self.end.wrapping_sub(&self.base)
+        };
+        let steps_left = distance / self.step.clone();
+        let mut incr_limit = T::from_limb_like(PROGRESSION_INCR_LIMIT.into(), &self.base);
+        if self.decreasing {
+            let half = self.base.wrapping_shr_vartime(1) / self.step.clone();
+            if half < incr_limit {
+                incr_limit = half;
+            }
+        }
+        if steps_left <= incr_limit {
+            // We are close to `end`.
+            // Mark this round as the last.
+            incr_limit = steps_left;
+            self.last_round = true;
+        }
+        self.incr_limit = incr_limit.as_ref()[0]
+            .0
+            .try_into()
+            .expect("the increment limit should fit within `Residue`");
+
+        // Only calculate residues by primes smaller than the lowest number of this round, because when we only
+        // have the residue, we cannot distinguish between a prime itself and a multiple of that prime.
+        let round_low = if self.decreasing {
+            self.base.wrapping_sub(
+                &self
+                    .step
+                    .as_ref()
+                    .wrapping_mul(&T::from_limb_like(self.incr_limit.into(), &self.base)),
+            )
+        } else {
+            self.base.clone()
+        };
+        let residues_len =
+            SMALL_PRIMES.partition_point(|x| T::from_limb_like(Residue::from(*x).into(), &round_low) < round_low);
+
+        // Re-calculate residues. This is taking up most of the sieving time.
+        self.residues.clear();
+        self.residues.extend(
+            RECIPROCALS
+                .iter()
+                .take(residues_len)
+                .map(|rec| self.base.rem_limb_with_reciprocal(rec).0 as SmallPrime),
+        );
+
+        true
+    }
+
+    /// Returns the next number of the progression for which `is_composite(r, d)` returns `false`
+    /// for its remainders `r` modulo all the small primes `d` used in the current round.
+    pub fn next_by(&mut self, is_composite: impl Fn(Residue, Residue) -> bool) -> Option<T> {
+        if self.exhausted {
+            return None;
+        }
+
+        while self.update_residues() {
+            let incr = u64::from(self.incr);
+            let composite = self
+                .residues
+                .iter()
+                .zip(self.step_residues.iter())
+                .zip(SMALL_PRIMES.iter())
+                .any(|((r, s), d)| {
+                    let d = u64::from(*d);
+                    let r = (u64::from(*r) + incr * u64::from(*s)) % d;
+                    is_composite(r as Residue, d as Residue)
+                });
+            let result = if composite { None } else { Some(self.current()) };
+            self.incr += 1;
+            if result.is_some() {
+                return result;
+            }
+        }
+
+        self.exhausted = true;
+        None
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T> Drop for ProgressionSieve<T>
+where
+    T: Unsigned,
+{
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+
+        wipe_integer(&mut self.base);
+        self.incr.zeroize();
+        self.residues.zeroize();
+    }
+}
+
+/// An iterator returning the numbers in the interval `[min, max]` in decreasing order,
+/// skipping the non-trivial multiples of the first 2048 small primes.
+///
+/// Visits the same residue classes as [`SmallFactorsSieve`] for the given `flavor`,
+/// and produces the exception (2 or 5, see [`SmallFactorsSieve::with_flavor`]) last.
+///
+/// If the `zeroize` feature is enabled, the secret state is wiped on drop.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct DecreasingSieve<T: Unsigned> {
+    // As in `SmallFactorsSieve`, for safe primes the progression is over the possible Germain primes.
+    progression: ProgressionSieve<T>,
+    safe_primes: bool,
+    exception: Option<T>,
+}
+
+impl<T> DecreasingSieve<T>
+where
+    T: Unsigned,
+{
+    pub fn new(min: T, max: T, flavor: Flavor) -> Self {
+        // Bring `min` to the precision of `max`, if it is larger (only matters for boxed integers).
+        let zero = T::zero_like(&max);
+        let min = zero.wrapping_add(&min);
+        let small = |x: u32| T::from_limb_like(x.into(), &zero);
+
+        // As in `SmallFactorsSieve`, for safe primes we iterate over the possible Germain primes `(n - 1)/2`,
+        // which have to be odd (since `n = 3 mod 4` for `n > 5`).
+        let exception = match flavor {
+            Flavor::Any => Some(small(2)),
+            Flavor::Safe => Some(small(5)),
+            Flavor::Blum => None,
+        }
+        .filter(|exception| &min <= exception && exception <= &max);
+        let safe_primes = flavor == Flavor::Safe;
+        let (min, max) = if safe_primes {
+            // `n = 2m + 1` is in `[min, max]` if `m` is in `[min / 2, (max - 1) / 2]`.
+            let max = match max.checked_sub(&small(1)).into_option() {
+                Some(max_minus_one) => max_minus_one.wrapping_shr_vartime(1),
+                None => max,
+            };
+            (min.wrapping_shr_vartime(1), max)
+        } else {
+            (min, max)
+        };
+
+        // The visited numbers are `2^k - 1` modulo `2^k`.
+        let k = if flavor == Flavor::Blum { 2 } else { 1 };
+        let class = small((1 << k) - 1);
+
+        // The smallest number in the class that is not smaller than `min` and 3,
+        // and the largest one that is not greater than `max`.
+        let low = min | class.clone();
+        let low = if low < small(3) { small(3) } else { low };
+        let top = if max < low {
+            // The progression is empty.
+            max
+        } else {
+            max.wrapping_sub(&class).wrapping_shr_vartime(k).wrapping_shl_vartime(k) | class
+        };
+
+        Self {
+            progression: ProgressionSieve::decreasing(top, low, small(1 << k)),
+            safe_primes,
+            exception,
+        }
+    }
+
+    fn next(&mut self) -> Option<T> {
+        let safe_primes = self.safe_primes;
+        // See `SmallFactorsSieve::current_is_composite()` for the safe prime check.
+        let num = self
+            .progression
+            .next_by(|r, d| r == 0 || (safe_primes && r == (d - 1) >> 1));
+        match num {
+            Some(num) if safe_primes => Some(num.wrapping_shl_vartime(1) | T::one_like(&num)),
+            Some(num) => Some(num),
+            None => self.exception.take(),
+        }
+    }
+}
+
+impl<T> Iterator for DecreasingSieve<T>
+where
+    T: Unsigned,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Self::next(self)
+    }
+}
+
 /// A type producing sieves for random prime generation.
 pub trait SieveFactory {
     /// The type of items returning by the sieves.
@@ -323,7 +746,7 @@ pub trait SieveFactory {
 #[derive(Debug, Clone, Copy)]
 pub struct SmallFactorsSieveFactory<T> {
     max_bit_length: NonZeroU32,
-    safe_primes: bool,
+    flavor: Flavor,
     set_bits: SetBits,
     phantom: PhantomData<T>,
 }
@@ -356,14 +779,19 @@ where
                     });
                 }
             }
+            Flavor::Blum => {
+                if max_bit_length < 2 {
+                    return Err(Error::BitLengthTooSmall {
+                        bit_length: max_bit_length,
+                        flavor,
+                    });
+                }
+            }
         }
         let max_bit_length = NonZero::new(max_bit_length).expect("`bit_length` should be non-zero");
         Ok(Self {
             max_bit_length,
-            safe_primes: match flavor {
-                Flavor::Any => false,
-                Flavor::Safe => true,
-            },
+            flavor,
             set_bits,
             phantom: PhantomData,
         })
@@ -385,10 +813,10 @@ where
         R: CryptoRng + ?Sized,
     {
         let start = random_odd_integer::<T, _>(rng, self.max_bit_length, self.set_bits)?;
-        Ok(Some(SmallFactorsSieve::new(
+        Ok(Some(SmallFactorsSieve::with_flavor(
             start.get(),
             self.max_bit_length,
-            self.safe_primes,
+            self.flavor,
         )?))
     }
 }
@@ -455,8 +883,8 @@ mod tests {
         }
     }
 
-    fn check_sieve(start: u32, bit_length: u32, safe_prime: bool, reference: &[u32]) {
-        let test = SmallFactorsSieve::new(U64::from(start), NonZero::new(bit_length).unwrap(), safe_prime)
+    fn check_sieve(start: u32, bit_length: u32, flavor: Flavor, reference: &[u32]) {
+        let test = SmallFactorsSieve::with_flavor(U64::from(start), NonZero::new(bit_length).unwrap(), flavor)
             .unwrap()
             .collect::<Vec<_>>();
         assert_eq!(test.len(), reference.len());
@@ -467,34 +895,34 @@ mod tests {
 
     #[test]
     fn empty_sequence() {
-        check_sieve(1, 1, false, &[]); // no primes of 1 bits
-        check_sieve(1, 2, true, &[]); // no safe primes of 2 bits
-        check_sieve(64, 6, true, &[]); // 64 is 7 bits long
+        check_sieve(1, 1, Flavor::Any, &[]); // no primes of 1 bits
+        check_sieve(1, 2, Flavor::Safe, &[]); // no safe primes of 2 bits
+        check_sieve(64, 6, Flavor::Safe, &[]); // 64 is 7 bits long
     }
 
     #[test]
     fn small_range() {
-        check_sieve(1, 2, false, &[2, 3]);
-        check_sieve(2, 2, false, &[2, 3]);
-        check_sieve(3, 2, false, &[3]);
-
-        check_sieve(1, 3, false, &[2, 3, 5, 7]);
-        check_sieve(3, 3, false, &[3, 5, 7]);
-        check_sieve(5, 3, false, &[5, 7]);
-        check_sieve(7, 3, false, &[7]);
-
-        check_sieve(1, 4, false, &[2, 3, 5, 7, 9, 11, 13, 15]);
-        check_sieve(3, 4, false, &[3, 5, 7, 9, 11, 13, 15]);
-        check_sieve(5, 4, false, &[5, 7, 11, 13]);
-        check_sieve(7, 4, false, &[7, 11, 13]);
-        check_sieve(9, 4, false, &[11, 13]);
-        check_sieve(13, 4, false, &[13]);
-        check_sieve(15, 4, false, &[]);
-
-        check_sieve(1, 3, true, &[5, 7]);
-        check_sieve(3, 3, true, &[5, 7]);
-        check_sieve(5, 3, true, &[5, 7]);
-        check_sieve(7, 3, true, &[7]);
+        check_sieve(1, 2, Flavor::Any, &[2, 3]);
+        check_sieve(2, 2, Flavor::Any, &[2, 3]);
+        check_sieve(3, 2, Flavor::Any, &[3]);
+
+        check_sieve(1, 3, Flavor::Any, &[2, 3, 5, 7]);
+        check_sieve(3, 3, Flavor::Any, &[3, 5, 7]);
+        check_sieve(5, 3, Flavor::Any, &[5, 7]);
+        check_sieve(7, 3, Flavor::Any, &[7]);
+
+        check_sieve(1, 4, Flavor::Any, &[2, 3, 5, 7, 9, 11, 13, 15]);
+        check_sieve(3, 4, Flavor::Any, &[3, 5, 7, 9, 11, 13, 15]);
+        check_sieve(5, 4, Flavor::Any, &[5, 7, 11, 13]);
+        check_sieve(7, 4, Flavor::Any, &[7, 11, 13]);
+        check_sieve(9, 4, Flavor::Any, &[11, 13]);
+        check_sieve(13, 4, Flavor::Any, &[13]);
+        check_sieve(15, 4, Flavor::Any, &[]);
+
+        check_sieve(1, 3, Flavor::Safe, &[5, 7]);
+        check_sieve(3, 3, Flavor::Safe, &[5, 7]);
+        check_sieve(5, 3, Flavor::Safe, &[5, 7]);
+        check_sieve(7, 3, Flavor::Safe, &[7]);
 
         // In the following three cases, the "half-start" would be set to 3,
         // and since every small divisor equal or greater than the start is not tested
@@ -502,12 +930,39 @@ mod tests {
         // and the number being actually equal to the divisor),
         // no divisors will actually be tested at all, so 15 (a composite)
         // is included in the output.
-        check_sieve(1, 4, true, &[5, 7, 11, 15]);
-        check_sieve(5, 4, true, &[5, 7, 11, 15]);
-        check_sieve(7, 4, true, &[7, 11, 15]);
+        check_sieve(1, 4, Flavor::Safe, &[5, 7, 11, 15]);
+        check_sieve(5, 4, Flavor::Safe, &[5, 7, 11, 15]);
+        check_sieve(7, 4, Flavor::Safe, &[7, 11, 15]);
+
+        check_sieve(9, 4, Flavor::Safe, &[11]);
+        check_sieve(13, 4, Flavor::Safe, &[]);
+
+        check_sieve(1, 1, Flavor::Blum, &[]);
+        check_sieve(1, 2, Flavor::Blum, &[3]);
+        check_sieve(4, 2, Flavor::Blum, &[]); // the start is adjusted to 7, which is 3 bits long
+        check_sieve(1, 4, Flavor::Blum, &[3, 7, 11, 15]);
+        check_sieve(5, 4, Flavor::Blum, &[7, 11]);
+        check_sieve(12, 4, Flavor::Blum, &[]);
+        check_sieve(8, 5, Flavor::Blum, &[11, 19, 23, 31]);
+    }
+
+    #[test]
+    fn random_blum() {
+        let mut rng = ChaCha8Rng::from_seed(*b"01234567890123456789012345678901");
+        let start = random_odd_integer::<U64, _>(&mut rng, NonZero::new(32).unwrap(), SetBits::Msb)
+            .unwrap()
+            .get();
+        for num in SmallFactorsSieve::with_flavor(start, NonZero::new(32).unwrap(), Flavor::Blum)
+            .unwrap()
+            .take(100)
+        {
+            let num_u64 = u64::from(num);
+            assert_eq!(num_u64 & 3, 3);
 
-        check_sieve(9, 4, true, &[11]);
-        check_sieve(13, 4, true, &[]);
+            let factors_and_powers = factorize64(num_u64);
+            let factors = factors_and_powers.into_keys().collect::<Vec<_>>();
+            assert!(factors[0] > SMALL_PRIMES[SMALL_PRIMES.len() - 1] as u64);
+        }
     }
 
     #[test]
@@ -582,6 +1037,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn too_few_bits_blum_primes() {
+        assert_eq!(
+            SmallFactorsSieveFactory::<U64>::new(Flavor::Blum, 1, SetBits::Msb).unwrap_err(),
+            Error::BitLengthTooSmall {
+                bit_length: 1,
+                flavor: Flavor::Blum
+            }
+        );
+    }
+
     #[test]
     fn set_bits() {
         let mut rng = rand::rng();
@@ -637,4 +1103,17 @@ mod tests {
             U256::from_be_hex("000000000E28CE6059E357411C67F6539AEF56F2B4653F0583D6A2195A9897BB")
         );
     }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn zeroize() {
+        use zeroize::Zeroize;
+
+        let mut sieve = SmallFactorsSieve::new(U64::from(12345u32), NonZero::new(32).unwrap(), false).unwrap();
+        assert!(sieve.next().is_some());
+        sieve.zeroize();
+        assert_eq!(sieve.base, U64::ZERO);
+        assert!(sieve.residues.iter().all(|r| *r == 0));
+        assert!(sieve.next().is_none());
+    }
 }
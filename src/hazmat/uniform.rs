@@ -0,0 +1,370 @@
+//! Sieve factories producing (close to) uniformly distributed primes.
+//!
+//! The incremental sieving used by [`SmallFactorsSieve`](`super::SmallFactorsSieve`) is fast,
+//! but the probability of a prime being returned is proportional to the size of the gap preceding it,
+//! so the resulting distribution is not uniform.
+//! The factories in this module draw a fresh random candidate for every sieve instead.
+
+use core::{
+    marker::PhantomData,
+    num::{NonZero, NonZeroU32},
+    option,
+};
+
+use crypto_bigint::{NonZero as CTNonZero, RandomBits, RandomMod, Unsigned};
+use rand_core::CryptoRng;
+
+use super::{
+    precomputed::{RECIPROCALS, SMALL_PRIMES},
+    sieve::{SetBits, SieveFactory, random_odd_integer, zero_with_bit_length},
+};
+use crate::{error::Error, presets::Flavor};
+
+/// Returns `true` if `num` has a small prime factor and is not equal to it.
+pub(crate) fn has_small_factor<T: Unsigned>(num: &T) -> bool {
+    SMALL_PRIMES
+        .iter()
+        .zip(RECIPROCALS.iter())
+        .any(|(prime, rec)| num.rem_limb_with_reciprocal(rec).0 == 0 && num != &T::from(*prime))
+}
+
+/// A sieve factory implementing rejection sampling: every sieve consists of a single fresh random candidate,
+/// which is only returned if it is not divisible by any of a number of small primes.
+///
+/// Since every candidate is sampled independently, the primes found this way are uniformly distributed
+/// among the odd primes of the requested flavor in the range defined by `max_bit_length` and `set_bits`.
+/// The cost is a larger number of random bits consumed, and a slower sieving compared to
+/// [`SmallFactorsSieveFactory`](`super::SmallFactorsSieveFactory`).
+///
+/// Only produces odd numbers (so the prime 2 is never returned).
+#[derive(Debug, Clone, Copy)]
+pub struct RejectionSamplingFactory<T> {
+    max_bit_length: NonZeroU32,
+    flavor: Flavor,
+    set_bits: SetBits,
+    phantom: PhantomData<T>,
+}
+
+impl<T> RejectionSamplingFactory<T>
+where
+    T: Unsigned + RandomBits,
+{
+    /// Creates a factory that produces candidates of at most `max_bit_length` bits
+    /// that are not divisible by a number of small factors.
+    ///
+    /// Some bits may be guaranteed to set depending on the requested `set_bits`.
+    ///
+    /// Depending on the requested `flavor`, additional filters may be applied.
+    pub fn new(flavor: Flavor, max_bit_length: u32, set_bits: SetBits) -> Result<Self, Error> {
+        let min_bit_length = match flavor {
+            Flavor::Any | Flavor::Blum => 2,
+            Flavor::Safe => 3,
+        };
+        if max_bit_length < min_bit_length {
+            return Err(Error::BitLengthTooSmall {
+                bit_length: max_bit_length,
+                flavor,
+            });
+        }
+        let max_bit_length = NonZero::new(max_bit_length).expect("`bit_length` should be non-zero");
+        Ok(Self {
+            max_bit_length,
+            flavor,
+            set_bits,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<T> SieveFactory for RejectionSamplingFactory<T>
+where
+    T: Unsigned + RandomBits,
+{
+    type Item = T;
+    type Sieve = option::IntoIter<T>;
+    fn make_sieve<R>(
+        &mut self,
+        rng: &mut R,
+        _previous_sieve: Option<&Self::Sieve>,
+    ) -> Result<Option<Self::Sieve>, Error>
+    where
+        R: CryptoRng + ?Sized,
+    {
+        let mut candidate = random_odd_integer::<T, _>(rng, self.max_bit_length, self.set_bits)?.get();
+
+        let passed = match self.flavor {
+            Flavor::Any => !has_small_factor(&candidate),
+            Flavor::Blum => {
+                // Setting the bit does not change the distribution among the numbers equal to 3 modulo 4.
+                candidate.set_bit_vartime(1, true);
+                !has_small_factor(&candidate)
+            }
+            Flavor::Safe => {
+                // 5 is the only safe prime that is not equal to 3 modulo 4.
+                // We can only hit it for tiny bit lengths, so we skip the bit setting in that case.
+                if candidate != T::from(5u32) {
+                    candidate.set_bit_vartime(1, true);
+                }
+                !has_small_factor(&candidate) && !has_small_factor(&candidate.wrapping_shr_vartime(1))
+            }
+        };
+
+        Ok(Some(if passed { Some(candidate) } else { None }.into_iter()))
+    }
+}
+
+/// A sieve factory implementing the close to uniform prime generation method by Fouque and Tibouchi[^FT].
+///
+/// Let `m` be the product of the small primes (including 2) that fits in half of `bit_length`.
+/// The factory picks a random unit `b` modulo `m` once, and then every sieve consists of a single candidate
+/// `a * m + b` of exactly `bit_length` bits, where `a` is sampled uniformly at random for each sieve.
+/// Since the candidates are coprime with `m` by construction, no sieving is necessary,
+/// and fewer random bits are consumed than with [`RejectionSamplingFactory`].
+/// The statistical distance of the resulting distribution of primes from the uniform one is negligible
+/// (see the paper for the exact bounds).
+///
+/// Only produces odd numbers (so the prime 2 is never returned).
+///
+/// [^FT]: P.-A. Fouque, M. Tibouchi, "Close to Uniform Prime Number Generation With Fewer Random Bits",
+///   ICALP 2014, LNCS 8572, 991-1002 (2014),
+///   DOI: [10.1007/978-3-662-43948-7_82](https://doi.org/10.1007/978-3-662-43948-7_82),
+///   <https://eprint.iacr.org/2011/481>
+#[derive(Debug, Clone)]
+pub struct FouqueTibouchiFactory<T> {
+    bit_length: u32,
+    modulus: CTNonZero<T>,
+    odd_primes_in_modulus: usize,
+    unit: Option<(T, T, CTNonZero<T>)>,
+}
+
+impl<T> FouqueTibouchiFactory<T>
+where
+    T: Unsigned + RandomBits + RandomMod,
+{
+    /// Creates a factory that produces candidates of exactly `bit_length` bits.
+    ///
+    /// Returns an error if `bit_length < 2`, or if `bit_length` is larger than the precision of `T`
+    /// (for fixed-size integers).
+    pub fn new(bit_length: u32) -> Result<Self, Error> {
+        if bit_length < 2 {
+            return Err(Error::BitLengthTooSmall {
+                bit_length,
+                flavor: Flavor::Any,
+            });
+        }
+
+        let zero = zero_with_bit_length::<T>(bit_length)?;
+        let mut modulus = zero.wrapping_add(&T::from(2u32));
+        let mut odd_primes_in_modulus = 0;
+        for prime in SMALL_PRIMES {
+            let new_modulus = modulus.wrapping_mul(&T::from(prime));
+            if new_modulus.bits_vartime() > bit_length / 2 {
+                break;
+            }
+            modulus = new_modulus;
+            odd_primes_in_modulus += 1;
+        }
+
+        Ok(Self {
+            bit_length,
+            modulus: CTNonZero::new(modulus).expect("the modulus is non-zero"),
+            odd_primes_in_modulus,
+            unit: None,
+        })
+    }
+
+    /// Picks a random unit `b` modulo `m`, and returns it along with the range of `a`
+    /// such that `a * m + b` has exactly `bit_length` bits, as the minimum value and the number of values.
+    fn pick_unit<R: CryptoRng + ?Sized>(&self, rng: &mut R) -> (T, T, CTNonZero<T>) {
+        let unit = loop {
+            let b = T::random_mod(rng, &self.modulus);
+            let is_unit = bool::from(b.is_odd())
+                && RECIPROCALS
+                    .iter()
+                    .take(self.odd_primes_in_modulus)
+                    .all(|rec| b.rem_limb_with_reciprocal(rec).0 != 0);
+            if is_unit {
+                break b;
+            }
+        };
+
+        // `a` is in `[ceil((2^(bit_length - 1) - b) / m), floor((2^bit_length - 1 - b) / m)]`.
+        let one = T::one_like(&unit);
+        let half = one.wrapping_shl_vartime(self.bit_length - 1);
+        let min_value = half.wrapping_sub(&unit);
+        let max_value = half.wrapping_sub(&one).wrapping_add(&half).wrapping_sub(&unit);
+        let a_max = max_value / &self.modulus;
+        let a_min = min_value.wrapping_add(&self.modulus.wrapping_sub(&one)) / &self.modulus;
+        let count = CTNonZero::new(a_max.wrapping_sub(&a_min).wrapping_add(&one))
+            .expect("the range of `a` is non-empty since `m <= 2^(bit_length / 2)`");
+
+        (unit, a_min, count)
+    }
+}
+
+impl<T> SieveFactory for FouqueTibouchiFactory<T>
+where
+    T: Unsigned + RandomBits + RandomMod,
+{
+    type Item = T;
+    type Sieve = option::IntoIter<T>;
+    fn make_sieve<R>(
+        &mut self,
+        rng: &mut R,
+        _previous_sieve: Option<&Self::Sieve>,
+    ) -> Result<Option<Self::Sieve>, Error>
+    where
+        R: CryptoRng + ?Sized,
+    {
+        if self.unit.is_none() {
+            self.unit = Some(self.pick_unit(rng));
+        }
+        let (unit, a_min, count) = self.unit.as_ref().expect("the unit has just been set");
+
+        let a = a_min.wrapping_add(&T::random_mod(rng, count));
+        let candidate = a.wrapping_mul(self.modulus.as_ref()).wrapping_add(unit);
+        Ok(Some(Some(candidate).into_iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crypto_bigint::{BoxedUint, U64, U128, U1024};
+    use num_prime::nt_funcs::is_prime64;
+
+    use super::{FouqueTibouchiFactory, RejectionSamplingFactory};
+    use crate::{
+        Error, Flavor,
+        hazmat::{SetBits, SieveFactory, precomputed::SMALL_PRIMES},
+        is_prime, sieve_and_find,
+    };
+
+    #[test]
+    fn rejection_sampling() {
+        let mut rng = rand::rng();
+        let max_prime = SMALL_PRIMES[SMALL_PRIMES.len() - 1] as u64;
+        for flavor in [Flavor::Any, Flavor::Safe, Flavor::Blum] {
+            let mut factory = RejectionSamplingFactory::<U64>::new(flavor, 40, SetBits::Msb).unwrap();
+            let mut count = 0;
+            for _ in 0..1000 {
+                let candidates = factory
+                    .make_sieve(&mut rng, None)
+                    .unwrap()
+                    .unwrap()
+                    .map(u64::from)
+                    .collect::<Vec<_>>();
+                assert!(candidates.len() <= 1);
+                for num in candidates {
+                    count += 1;
+                    assert_eq!(num >> 39, 1);
+                    assert!(
+                        SMALL_PRIMES
+                            .iter()
+                            .all(|p| num % *p as u64 != 0 || *p as u64 > max_prime)
+                    );
+                    if flavor != Flavor::Any {
+                        assert_eq!(num & 3, 3);
+                    }
+                }
+            }
+            assert!(count > 0);
+        }
+    }
+
+    #[test]
+    fn rejection_sampling_small_primes() {
+        // Check that tiny primes are not filtered out.
+        let mut rng = rand::rng();
+        for (flavor, bit_length) in [(Flavor::Any, 2), (Flavor::Any, 3), (Flavor::Blum, 2), (Flavor::Safe, 3)] {
+            let factory = RejectionSamplingFactory::<U64>::new(flavor, bit_length, SetBits::Msb).unwrap();
+            let p = sieve_and_find(&mut rng, factory, |_rng, num| is_prime(flavor, num))
+                .unwrap()
+                .unwrap();
+            assert!(is_prime64(p.into()));
+        }
+
+        assert_eq!(
+            RejectionSamplingFactory::<U64>::new(Flavor::Safe, 2, SetBits::Msb).unwrap_err(),
+            Error::BitLengthTooSmall {
+                bit_length: 2,
+                flavor: Flavor::Safe
+            }
+        );
+    }
+
+    #[test]
+    fn rejection_sampling_distribution() {
+        // There are 2 primes with 3 bits (5 and 7), both should be hit.
+        let mut rng = rand::rng();
+        let factory = RejectionSamplingFactory::<U64>::new(Flavor::Any, 3, SetBits::Msb).unwrap();
+        let mut hits = [0; 2];
+        for _ in 0..200 {
+            let p = sieve_and_find(&mut rng, factory, |_rng, num| is_prime(Flavor::Any, num))
+                .unwrap()
+                .unwrap();
+            hits[(u64::from(p) == 7) as usize] += 1;
+        }
+        assert!(hits[0] > 50 && hits[1] > 50);
+    }
+
+    #[test]
+    fn fouque_tibouchi() {
+        let mut rng = rand::rng();
+        for bit_length in [2, 3, 4, 10, 20, 40, 64] {
+            let factory = FouqueTibouchiFactory::<U64>::new(bit_length).unwrap();
+            let p = sieve_and_find(&mut rng, factory, |_rng, num| is_prime(Flavor::Any, num))
+                .unwrap()
+                .unwrap();
+            assert_eq!(p.bits_vartime(), bit_length);
+            assert!(is_prime64(p.into()));
+        }
+
+        let factory = FouqueTibouchiFactory::<U1024>::new(1024).unwrap();
+        let p = sieve_and_find(&mut rng, factory, |_rng, num| is_prime(Flavor::Any, num))
+            .unwrap()
+            .unwrap();
+        assert_eq!(p.bits_vartime(), 1024);
+        assert!(is_prime(Flavor::Any, &p));
+
+        let factory = FouqueTibouchiFactory::<BoxedUint>::new(200).unwrap();
+        let p = sieve_and_find(&mut rng, factory, |_rng, num| is_prime(Flavor::Any, num))
+            .unwrap()
+            .unwrap();
+        assert_eq!(p.bits_vartime(), 200);
+        assert!(is_prime(Flavor::Any, &p));
+    }
+
+    #[test]
+    fn fouque_tibouchi_candidates() {
+        let mut rng = rand::rng();
+        let mut factory = FouqueTibouchiFactory::<U128>::new(128).unwrap();
+        for _ in 0..100 {
+            let candidate = factory.make_sieve(&mut rng, None).unwrap().unwrap().next().unwrap();
+            assert_eq!(candidate.bits_vartime(), 128);
+            // Coprime with the modulus, which includes at least the first few primes.
+            for prime in [2u32, 3, 5, 7, 11, 13] {
+                assert_ne!(
+                    candidate.rem_limb(crypto_bigint::Limb::from(prime).to_nz().unwrap()).0,
+                    0
+                );
+            }
+        }
+
+        assert_eq!(
+            FouqueTibouchiFactory::<U64>::new(1).unwrap_err(),
+            Error::BitLengthTooSmall {
+                bit_length: 1,
+                flavor: Flavor::Any
+            }
+        );
+        assert_eq!(
+            FouqueTibouchiFactory::<U64>::new(65).unwrap_err(),
+            Error::BitLengthTooLarge {
+                bit_length: 65,
+                bits_precision: 64
+            }
+        );
+    }
+}
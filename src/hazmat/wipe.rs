@@ -0,0 +1,93 @@
+//! Wiping of intermediate values derived from secret candidates.
+//!
+//! Only does anything when the `zeroize` feature is enabled.
+
+use core::ops::{Deref, DerefMut};
+
+use crypto_bigint::{Integer, Monty};
+
+/// Overwrites the limbs of `num` with zeros.
+#[cfg(feature = "zeroize")]
+pub(crate) fn wipe_integer<T: Integer>(num: &mut T) {
+    zeroize::Zeroize::zeroize(num.as_mut_limbs());
+}
+
+/// Overwrites the Montgomery representation of `num` with zero.
+///
+/// The Montgomery parameters `num` refers to are left intact:
+/// `crypto-bigint` does not provide a way to wipe them for a generic integer type.
+#[cfg(feature = "zeroize")]
+pub(crate) fn wipe_monty<M: Monty>(num: &mut M) {
+    let zero = M::zero(num.params().clone());
+    num.copy_montgomery_from(&zero);
+    // Make sure the write is not optimized away as a dead store.
+    core::hint::black_box(&*num);
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+/// A value in Montgomery form that is wiped with [`wipe_monty`] on drop when the `zeroize` feature is enabled.
+pub(crate) struct WipedMonty<M: Monty>(pub(crate) M);
+
+impl<M: Monty> Deref for WipedMonty<M> {
+    type Target = M;
+    fn deref(&self) -> &M {
+        &self.0
+    }
+}
+
+impl<M: Monty> DerefMut for WipedMonty<M> {
+    fn deref_mut(&mut self) -> &mut M {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<M: Monty> Drop for WipedMonty<M> {
+    fn drop(&mut self) {
+        wipe_monty(&mut self.0);
+    }
+}
+
+/// An integer that is wiped with [`wipe_integer`] on drop when the `zeroize` feature is enabled.
+pub(crate) struct WipedInteger<T: Integer>(pub(crate) T);
+
+impl<T: Integer> Deref for WipedInteger<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: Integer> zeroize::Zeroize for WipedInteger<T> {
+    fn zeroize(&mut self) {
+        wipe_integer(&mut self.0);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: Integer> Drop for WipedInteger<T> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn zeroize() {
+        use crypto_bigint::{BoxedUint, U128};
+        use zeroize::Zeroize;
+
+        use super::WipedInteger;
+
+        let mut num = WipedInteger(U128::from(0x1234_5678_9abc_def0_u64));
+        num.zeroize();
+        assert_eq!(*num, U128::ZERO);
+
+        let mut num = WipedInteger(BoxedUint::one_with_precision(256).wrapping_shl_vartime(200));
+        num.zeroize();
+        assert_eq!(*num, BoxedUint::zero_with_precision(256));
+    }
+}
@@ -16,15 +16,31 @@
 
 extern crate alloc;
 
+mod budget;
+pub mod certified;
 mod error;
 pub mod fips;
 mod generic;
 pub mod hazmat;
+mod ntt;
 mod presets;
+mod schnorr_group;
+mod strong_prime;
 
 #[cfg(feature = "multicore")]
 pub mod multicore;
 
+pub use budget::Budget;
 pub use error::Error;
-pub use generic::sieve_and_find;
-pub use presets::{Flavor, is_prime, random_prime};
+pub use generic::{sieve_and_find, sieve_and_find_with_budget};
+pub use ntt::{NttPrime, ntt_prime_chain, random_ntt_prime};
+pub use presets::{
+    CunninghamKind, Flavor, PrimesInRange, is_cunningham_chain, is_prime, is_prime_ct, next_prime, prev_prime,
+    random_cunningham_chain, random_prime, random_prime_congruent, random_prime_ct, random_prime_in_range,
+    random_prime_in_range_with_budget, random_prime_uniform, try_random_cunningham_chain,
+    try_random_cunningham_chain_with_budget, try_random_prime, try_random_prime_congruent,
+    try_random_prime_congruent_with_budget, try_random_prime_ct, try_random_prime_ct_with_budget,
+    try_random_prime_uniform, try_random_prime_uniform_with_budget, try_random_prime_with_budget,
+};
+pub use schnorr_group::{SchnorrGroup, random_schnorr_group, random_schnorr_group_with_budget};
+pub use strong_prime::{StrongPrime, random_strong_prime, random_strong_prime_with_budget};
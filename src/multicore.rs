@@ -5,6 +5,7 @@ use rand_core::{CryptoRng, SeedableRng};
 use rayon::iter::{ParallelBridge, ParallelIterator};
 
 use crate::{
+    budget::{Budget, BudgetTracker},
     error::Error,
     hazmat::{SetBits, SieveFactory, SmallFactorsSieveFactory},
     presets::{Flavor, is_prime},
@@ -14,6 +15,9 @@ use crate::{
 /// and returns the first item for which `predicate` is `true`.
 ///
 /// If `sieve_factory` signals that no more results can be created, returns `None`.
+///
+/// Returns [`Error::ThreadPool`] if the thread pool could not be created,
+/// or the error returned by `sieve_factory`, if any.
 pub fn sieve_and_find<R, S, F>(
     rng: &mut R,
     sieve_factory: S,
@@ -27,40 +31,97 @@ where
     S::Item: Send,
     F: Sync + Fn(&mut R, &S::Item) -> bool,
 {
-    let threadpool = rayon::ThreadPoolBuilder::new()
-        .num_threads(threadcount)
-        .build()
-        .expect("If the platform can spawn threads, then this call will work.");
-
     let iter = match SieveIterator::new(rng, sieve_factory)? {
         Some(iter) => iter,
         None => return Ok(None),
     };
+    find_in_parallel(iter, predicate, threadcount)
+}
+
+/// Same as [`sieve_and_find`], but stops with [`Error::AttemptsExhausted`]
+/// when the limits set by `budget` are reached.
+///
+/// The candidates are counted as they are handed out to the threads,
+/// so no more than the allowed number of candidates is tested.
+pub fn sieve_and_find_with_budget<R, S, F>(
+    rng: &mut R,
+    sieve_factory: S,
+    predicate: F,
+    threadcount: usize,
+    budget: Budget<'_>,
+) -> Result<Option<S::Item>, Error>
+where
+    R: CryptoRng + Send + Sync + SeedableRng,
+    S: Send + Sync + SieveFactory,
+    S::Sieve: Send,
+    S::Item: Send,
+    F: Sync + Fn(&mut R, &S::Item) -> bool,
+{
+    let iter = match SieveIterator::new_with_budget(rng, sieve_factory, budget)? {
+        Some(iter) => iter,
+        None => return Ok(None),
+    };
+    find_in_parallel(iter, predicate, threadcount)
+}
+
+/// Returns the first item produced by `iter` for which `predicate` is `true`,
+/// using a thread pool with `threadcount` threads.
+fn find_in_parallel<R, S, F>(
+    iter: SieveIterator<'_, '_, R, S>,
+    predicate: F,
+    threadcount: usize,
+) -> Result<Option<S::Item>, Error>
+where
+    R: CryptoRng + Send + Sync + SeedableRng,
+    S: Send + Sync + SieveFactory,
+    S::Sieve: Send,
+    S::Item: Send,
+    F: Sync + Fn(&mut R, &S::Item) -> bool,
+{
+    let threadpool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threadcount)
+        .build()
+        .map_err(|_| Error::ThreadPool)?;
 
     threadpool.install(|| {
-        Ok(iter
-            .par_bridge()
-            .find_map_any(|(mut rng, c)| if predicate(&mut rng, &c) { Some(c) } else { None }))
+        iter.par_bridge()
+            .find_map_any(|item| match item {
+                Ok((mut rng, c)) => predicate(&mut rng, &c).then_some(Ok(c)),
+                Err(err) => Some(Err(err)),
+            })
+            .transpose()
     })
 }
 
 /// A structure that chains the creation of sieves, returning the results from one until it is exhausted,
 /// and then creating a new one.
+///
+/// If the creation of a sieve fails, or the budget is exhausted, the error is returned, and the iteration stops.
 #[derive(Debug)]
-struct SieveIterator<'a, R: ?Sized, S: SieveFactory> {
+struct SieveIterator<'a, 'b, R: ?Sized, S: SieveFactory> {
     sieve_factory: S,
     sieve: S::Sieve,
     rng: &'a mut R,
+    tracker: BudgetTracker<'b>,
+    failed: bool,
 }
 
-impl<'a, R, S> SieveIterator<'a, R, S>
+impl<'a, 'b, R, S> SieveIterator<'a, 'b, R, S>
 where
     R: CryptoRng + ?Sized,
     S: SieveFactory,
 {
     /// Creates a new chained iterator producing results from sieves returned from `sieve_factory`.
     pub fn new(rng: &'a mut R, sieve_factory: S) -> Result<Option<Self>, Error> {
+        Self::new_with_budget(rng, sieve_factory, Budget::unlimited())
+    }
+
+    /// Same as [`new`](`Self::new`), but the iteration stops with [`Error::AttemptsExhausted`]
+    /// when the limits set by `budget` are reached.
+    pub fn new_with_budget(rng: &'a mut R, sieve_factory: S, budget: Budget<'b>) -> Result<Option<Self>, Error> {
+        let mut tracker = BudgetTracker::new(budget);
         let mut sieve_factory = sieve_factory;
+        tracker.add_sieve()?;
         let sieve = match sieve_factory.make_sieve(rng, None)? {
             Some(sieve) => sieve,
             None => return Ok(None),
@@ -69,27 +130,45 @@ where
             sieve_factory,
             rng,
             sieve,
+            tracker,
+            failed: false,
         }))
     }
 }
 
-impl<R, S> Iterator for SieveIterator<'_, R, S>
+impl<R, S> Iterator for SieveIterator<'_, '_, R, S>
 where
     R: CryptoRng + SeedableRng,
     S: SieveFactory,
 {
-    type Item = (R, S::Item);
+    type Item = Result<(R, S::Item), Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
         loop {
             if let Some(result) = self.sieve.next() {
-                return Some((R::from_rng(self.rng), result));
+                if let Err(err) = self.tracker.add_candidate() {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+                return Some(Ok((R::from_rng(self.rng), result)));
             }
 
-            self.sieve = self
-                .sieve_factory
-                .make_sieve(self.rng, Some(&self.sieve))
-                .expect("the first attempt to make a sieve succeeded, so the next ones should too")?;
+            if let Err(err) = self.tracker.add_sieve() {
+                self.failed = true;
+                return Some(Err(err));
+            }
+            match self.sieve_factory.make_sieve(self.rng, Some(&self.sieve)) {
+                Ok(Some(sieve)) => self.sieve = sieve,
+                Ok(None) => return None,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err));
+                }
+            }
         }
     }
 }
@@ -103,6 +182,8 @@ where
 /// Panics if `bit_length` is less than the bit length of the smallest possible prime with the requested `flavor`.
 ///
 /// Panics if the platform is unable to spawn threads.
+///
+/// See [`try_random_prime`] for a non-panicking version.
 pub fn random_prime<T, R>(rng: &mut R, flavor: Flavor, bit_length: u32, threadcount: usize) -> T
 where
     T: Unsigned + RandomBits + RandomMod,
@@ -111,18 +192,58 @@ where
     let factory = SmallFactorsSieveFactory::new(flavor, bit_length, SetBits::Msb)
         .unwrap_or_else(|err| panic!("Error creating the sieve: {err}"));
     sieve_and_find(rng, factory, |_rng, candidate| is_prime(flavor, candidate), threadcount)
-        .unwrap_or_else(|err| panic!("Error generating random candidates: {}", err))
+        .unwrap_or_else(|err| panic!("Error generating random candidates: {err}"))
         .expect("will produce a result eventually")
 }
 
+/// Returns a random prime of size `bit_length` using the provided RNG.
+///
+/// Same as [`random_prime`], but returns an error instead of panicking:
+/// [`Error::BitLengthTooSmall`] or [`Error::BitLengthTooLarge`] if `bit_length` is out of range,
+/// and [`Error::ThreadPool`] if the platform is unable to spawn threads.
+pub fn try_random_prime<T, R>(rng: &mut R, flavor: Flavor, bit_length: u32, threadcount: usize) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + Send + Sync + SeedableRng,
+{
+    try_random_prime_with_budget(rng, flavor, bit_length, threadcount, Budget::unlimited())
+}
+
+/// Same as [`try_random_prime`], but returns [`Error::AttemptsExhausted`]
+/// if the limits set by `budget` were reached.
+pub fn try_random_prime_with_budget<T, R>(
+    rng: &mut R,
+    flavor: Flavor,
+    bit_length: u32,
+    threadcount: usize,
+    budget: Budget<'_>,
+) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + Send + Sync + SeedableRng,
+{
+    let factory = SmallFactorsSieveFactory::new(flavor, bit_length, SetBits::Msb)?;
+    sieve_and_find_with_budget(
+        rng,
+        factory,
+        |_rng, candidate| is_prime(flavor, candidate),
+        threadcount,
+        budget,
+    )?
+    .ok_or(Error::NoPrimesInRange { flavor })
+}
+
 #[cfg(test)]
 mod tests {
-    use crypto_bigint::{BoxedUint, U128, nlimbs};
+    use crypto_bigint::{BoxedUint, U64, U128, nlimbs};
     use rand::rngs::ChaCha12Rng;
-    use rand_core::SeedableRng;
+    use rand_core::{CryptoRng, SeedableRng};
 
-    use super::{is_prime, random_prime};
-    use crate::Flavor;
+    use super::{
+        is_prime, random_prime, sieve_and_find, sieve_and_find_with_budget, try_random_prime,
+        try_random_prime_with_budget,
+    };
+    use crate::{Budget, Error, Flavor, hazmat::SieveFactory};
 
     #[test]
     fn parallel_prime_generation() {
@@ -165,4 +286,94 @@ mod tests {
             assert!(is_prime(Flavor::Safe, &p));
         }
     }
+
+    #[test]
+    fn parallel_prime_generation_errors() {
+        let mut rng = ChaCha12Rng::from_rng(&mut rand::rng());
+        assert_eq!(
+            try_random_prime::<U64, _>(&mut rng, Flavor::Safe, 2, 2),
+            Err(Error::BitLengthTooSmall {
+                bit_length: 2,
+                flavor: Flavor::Safe
+            })
+        );
+        assert_eq!(
+            try_random_prime::<U64, _>(&mut rng, Flavor::Any, 65, 2),
+            Err(Error::BitLengthTooLarge {
+                bit_length: 65,
+                bits_precision: 64
+            })
+        );
+        assert_eq!(
+            try_random_prime_with_budget::<U128, _>(
+                &mut rng,
+                Flavor::Any,
+                128,
+                2,
+                Budget::unlimited().with_max_candidates(0)
+            ),
+            Err(Error::AttemptsExhausted { attempts: 0 })
+        );
+    }
+
+    #[test]
+    fn failing_sieve_factory() {
+        // Check that an error from a sieve factory after the first sieve is propagated.
+        struct TestSieveFactory;
+
+        impl SieveFactory for TestSieveFactory {
+            type Item = usize;
+            type Sieve = core::ops::Range<usize>;
+
+            fn make_sieve<R: CryptoRng + ?Sized>(
+                &mut self,
+                _rng: &mut R,
+                previous_sieve: Option<&Self::Sieve>,
+            ) -> Result<Option<Self::Sieve>, Error> {
+                match previous_sieve {
+                    None => Ok(Some(0..10)),
+                    Some(_) => Err(Error::AttemptsExhausted { attempts: 1 }),
+                }
+            }
+        }
+
+        let mut rng = ChaCha12Rng::from_rng(&mut rand::rng());
+        let result = sieve_and_find(&mut rng, TestSieveFactory, |_rng, num| *num == 5, 2);
+        assert_eq!(result, Ok(Some(5)));
+        let result = sieve_and_find(&mut rng, TestSieveFactory, |_rng, num| *num == 20, 2);
+        assert_eq!(result, Err(Error::AttemptsExhausted { attempts: 1 }));
+    }
+
+    #[test]
+    fn budget() {
+        struct TestSieveFactory;
+
+        impl SieveFactory for TestSieveFactory {
+            type Item = usize;
+            type Sieve = core::ops::Range<usize>;
+
+            fn make_sieve<R: CryptoRng + ?Sized>(
+                &mut self,
+                _rng: &mut R,
+                previous_sieve: Option<&Self::Sieve>,
+            ) -> Result<Option<Self::Sieve>, Error> {
+                let start = previous_sieve.map_or(0, |sieve| sieve.end);
+                Ok(Some(start..start + 10))
+            }
+        }
+
+        let mut rng = ChaCha12Rng::from_rng(&mut rand::rng());
+
+        let budget = Budget::unlimited().with_max_sieves(3);
+        let result = sieve_and_find_with_budget(&mut rng, TestSieveFactory, |_rng, num| *num == 25, 2, budget);
+        assert_eq!(result, Ok(Some(25)));
+
+        let budget = Budget::unlimited().with_max_sieves(2);
+        let result = sieve_and_find_with_budget(&mut rng, TestSieveFactory, |_rng, num| *num == 25, 2, budget);
+        assert_eq!(result, Err(Error::AttemptsExhausted { attempts: 20 }));
+
+        let budget = Budget::unlimited().with_max_candidates(15);
+        let result = sieve_and_find_with_budget(&mut rng, TestSieveFactory, |_rng, num| *num == 25, 2, budget);
+        assert_eq!(result, Err(Error::AttemptsExhausted { attempts: 15 }));
+    }
 }
@@ -0,0 +1,242 @@
+//! Generation of NTT-friendly primes, that is primes `p = 1 mod 2N` for a power of two `2N`.
+
+use alloc::vec::Vec;
+
+use crypto_bigint::{Limb, Monty, Odd, PowBoundedExp, RandomBits, RandomMod, Square, Unsigned, modular::Retrieve};
+use rand_core::CryptoRng;
+
+use crate::{
+    error::Error,
+    generic::sieve_and_find,
+    hazmat::{CongruenceSieveFactory, zero_with_bit_length},
+    presets::{Flavor, is_prime},
+};
+
+/// An NTT-friendly prime generated by [`random_ntt_prime`] or [`ntt_prime_chain`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NttPrime<T> {
+    /// The prime `p = 1 mod 2^two_adicity`.
+    pub prime: T,
+    /// A primitive `2^two_adicity`-th root of unity modulo `p`.
+    pub root: T,
+}
+
+/// Returns a random prime `p` of size `bit_length` such that `p = 1 mod 2^two_adicity`,
+/// along with a primitive `2^two_adicity`-th root of unity modulo `p`.
+///
+/// In the terms commonly used for the number-theoretic transform (NTT) of size `N` over `Z_p[X]/(X^N + 1)`,
+/// `two_adicity` is `log2(2N)`.
+///
+/// The returned prime will have its MSB set. The candidates are checked with [`is_prime`].
+///
+/// Returns an error if `two_adicity` is zero or greater than `bit_length - 2`,
+/// or if `bit_length` is larger than the precision of `T` (for fixed-size integers).
+/// If `two_adicity` is close to `bit_length`, the residue class contains only a few numbers of size `bit_length`,
+/// and if none of them are prime, [`Error::NoPrimesInRange`] is returned.
+pub fn random_ntt_prime<T, R>(rng: &mut R, bit_length: u32, two_adicity: u32) -> Result<NttPrime<T>, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    check_parameters(bit_length, two_adicity)?;
+
+    let zero = zero_with_bit_length::<T>(bit_length)?;
+    let one = T::one_like(&zero);
+    let modulus = one.wrapping_shl_vartime(two_adicity);
+
+    let factory = CongruenceSieveFactory::new(bit_length, one, modulus)?;
+    let prime = sieve_and_find(rng, factory, |_rng, candidate| is_prime(Flavor::Any, candidate))?
+        .ok_or(Error::NoPrimesInRange { flavor: Flavor::Any })?;
+    let root = primitive_root_of_unity(&prime, two_adicity);
+
+    Ok(NttPrime { prime, root })
+}
+
+/// Returns `count` largest primes `p` of size `bit_length` such that `p = 1 mod 2^two_adicity`,
+/// in decreasing order, along with primitive `2^two_adicity`-th roots of unity modulo each of them.
+///
+/// This is useful for residue number system (RNS) representations, where a chain of distinct primes
+/// close to `2^bit_length` is needed. The result is deterministic: the candidates are scanned downward
+/// from `2^bit_length` and checked with [`is_prime`].
+///
+/// Returns an error if `two_adicity` is zero or greater than `bit_length - 2`,
+/// if `bit_length` is larger than the precision of `T` (for fixed-size integers),
+/// or if there are less than `count` such primes of size `bit_length`.
+pub fn ntt_prime_chain<T>(bit_length: u32, two_adicity: u32, count: usize) -> Result<Vec<NttPrime<T>>, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+{
+    check_parameters(bit_length, two_adicity)?;
+
+    let zero = zero_with_bit_length::<T>(bit_length)?;
+    let one = T::one_like(&zero);
+    let step = one.wrapping_shl_vartime(two_adicity);
+
+    // The largest candidate is `2^bit_length - 2^two_adicity + 1`, calculated in a way that does not overflow.
+    let half = one.wrapping_shl_vartime(bit_length - 1);
+    let mut candidate = half.wrapping_sub(&step).wrapping_add(&half).wrapping_add(&one);
+
+    let mut primes = Vec::with_capacity(count);
+    while primes.len() < count {
+        if candidate.bits_vartime() < bit_length {
+            return Err(Error::NoPrimesInRange { flavor: Flavor::Any });
+        }
+        if is_prime(Flavor::Any, &candidate) {
+            let root = primitive_root_of_unity(&candidate, two_adicity);
+            primes.push(NttPrime {
+                prime: candidate.clone(),
+                root,
+            });
+        }
+        candidate = candidate.wrapping_sub(&step);
+    }
+
+    Ok(primes)
+}
+
+fn check_parameters(bit_length: u32, two_adicity: u32) -> Result<(), Error> {
+    // The residue class needs to have at least one `bit_length`-bit element for any choice of the starting point,
+    // so `2^two_adicity` must be smaller than `2^(bit_length - 1)`.
+    if two_adicity == 0 || two_adicity.saturating_add(2) > bit_length {
+        return Err(Error::InvalidParameter { name: "two_adicity" });
+    }
+    Ok(())
+}
+
+/// Returns a primitive `2^two_adicity`-th root of unity modulo a prime `p = 1 mod 2^two_adicity`.
+///
+/// For `x = 2, 3, ...`, `w = x^((p - 1) / 2^two_adicity)` is a `2^two_adicity`-th root of unity,
+/// and it is primitive if and only if `w^(2^(two_adicity - 1)) = -1`, which happens if `x` is a quadratic nonresidue.
+fn primitive_root_of_unity<T>(prime: &T, two_adicity: u32) -> T
+where
+    T: Unsigned,
+{
+    let prime_odd = Odd::new(prime.clone()).expect("`prime` is an odd prime");
+    let params = <T as Unsigned>::Monty::new_params_vartime(prime_odd);
+    let one = T::one_like(prime);
+    let minus_one = prime.wrapping_sub(&one);
+    let exponent = prime.wrapping_shr_vartime(two_adicity);
+
+    let mut x: u32 = 2;
+    loop {
+        let x_monty = <T as Unsigned>::Monty::new(T::from_limb_like(Limb::from(x), prime), params.clone());
+        let root = x_monty.pow_bounded_exp(&exponent, exponent.bits_vartime());
+        let mut power = root.clone();
+        for _ in 1..two_adicity {
+            power = power.square();
+        }
+        if power.retrieve() == minus_one {
+            return root.retrieve();
+        }
+        x += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crypto_bigint::{BoxedUint, Monty, Odd, PowBoundedExp, RandomMod, U64, U256, Unsigned, modular::Retrieve};
+    use num_prime::nt_funcs::is_prime64;
+
+    use super::{NttPrime, ntt_prime_chain, random_ntt_prime};
+    use crate::{Error, Flavor, is_prime};
+
+    fn check_ntt_prime<T: Unsigned + RandomMod>(ntt_prime: &NttPrime<T>, bit_length: u32, two_adicity: u32) {
+        let NttPrime { prime, root } = ntt_prime;
+        assert_eq!(prime.bits_vartime(), bit_length);
+        assert!(is_prime(Flavor::Any, prime));
+
+        let one = T::one_like(prime);
+        let low_bits = prime
+            .wrapping_sub(&one)
+            .wrapping_shl_vartime(prime.bits_precision() - two_adicity);
+        assert!(bool::from(low_bits.is_zero()));
+
+        // `root` has the order exactly `2^two_adicity`.
+        let params = <T as Unsigned>::Monty::new_params_vartime(Odd::new(prime.clone()).unwrap());
+        let root = <T as Unsigned>::Monty::new(root.clone(), params);
+        let order = one.wrapping_shl_vartime(two_adicity);
+        let half_order = one.wrapping_shl_vartime(two_adicity - 1);
+        assert_eq!(root.pow_bounded_exp(&order, order.bits_vartime()).retrieve(), one);
+        assert_eq!(
+            root.pow_bounded_exp(&half_order, half_order.bits_vartime()).retrieve(),
+            prime.wrapping_sub(&one)
+        );
+    }
+
+    #[test]
+    fn random_ntt_primes() {
+        let mut rng = rand::rng();
+        for (bit_length, two_adicity) in [(64, 1), (64, 17), (64, 48), (256, 128)] {
+            let p = random_ntt_prime::<U256, _>(&mut rng, bit_length, two_adicity).unwrap();
+            check_ntt_prime(&p, bit_length, two_adicity);
+        }
+
+        let p = random_ntt_prime::<BoxedUint, _>(&mut rng, 200, 16).unwrap();
+        check_ntt_prime(&p, 200, 16);
+    }
+
+    #[test]
+    fn chain() {
+        // The "Goldilocks" prime `2^64 - 2^32 + 1` is the first one in the chain.
+        let chain = ntt_prime_chain::<U64>(64, 32, 3).unwrap();
+        assert_eq!(chain[0].prime, U64::from(0xffff_ffff_0000_0001u64));
+        for p in chain.iter() {
+            check_ntt_prime(p, 64, 32);
+        }
+
+        // Compare with a brute-force search.
+        let chain = ntt_prime_chain::<U64>(30, 12, 10).unwrap();
+        let reference = (1u64 << 29..1u64 << 30)
+            .rev()
+            .filter(|x| x % (1 << 12) == 1 && is_prime64(*x))
+            .take(10)
+            .collect::<Vec<_>>();
+        assert_eq!(chain.iter().map(|p| u64::from(p.prime)).collect::<Vec<_>>(), reference);
+        for p in chain.iter() {
+            check_ntt_prime(p, 30, 12);
+        }
+
+        let chain = ntt_prime_chain::<BoxedUint>(128, 40, 2).unwrap();
+        for p in chain.iter() {
+            check_ntt_prime(p, 128, 40);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let mut rng = rand::rng();
+        assert_eq!(
+            random_ntt_prime::<U64, _>(&mut rng, 64, 0).unwrap_err(),
+            Error::InvalidParameter { name: "two_adicity" }
+        );
+        assert_eq!(
+            random_ntt_prime::<U64, _>(&mut rng, 64, 63).unwrap_err(),
+            Error::InvalidParameter { name: "two_adicity" }
+        );
+        assert_eq!(
+            random_ntt_prime::<U64, _>(&mut rng, 65, 16).unwrap_err(),
+            Error::BitLengthTooLarge {
+                bit_length: 65,
+                bits_precision: 64
+            }
+        );
+        // Not enough primes of the form `16k + 1` with 6 bits (in fact, there are none).
+        let count = (32u64..64).filter(|x| x % 16 == 1 && is_prime64(*x)).count();
+        assert_eq!(count, 0);
+        assert_eq!(
+            random_ntt_prime::<U64, _>(&mut rng, 6, 4).unwrap_err(),
+            Error::NoPrimesInRange { flavor: Flavor::Any }
+        );
+        assert_eq!(
+            random_ntt_prime::<BoxedUint, _>(&mut rng, 6, 4).unwrap_err(),
+            Error::NoPrimesInRange { flavor: Flavor::Any }
+        );
+        assert_eq!(ntt_prime_chain::<U64>(6, 4, count).unwrap().len(), count);
+        assert_eq!(
+            ntt_prime_chain::<U64>(6, 4, count + 1).unwrap_err(),
+            Error::NoPrimesInRange { flavor: Flavor::Any }
+        );
+    }
+}
@@ -1,27 +1,53 @@
-use crypto_bigint::{Odd, RandomBits, RandomMod, Unsigned};
+use crypto_bigint::{
+    Odd, RandomBits, RandomMod, Uint, Unsigned,
+    subtle::{Choice, ConditionallySelectable, ConstantTimeEq},
+};
 use rand_core::CryptoRng;
 
 use crate::{
-    generic::sieve_and_find,
+    budget::Budget,
+    error::Error,
+    generic::{sieve_and_find, sieve_and_find_with_budget},
     hazmat::{
-        AStarBase, LucasCheck, MillerRabin, Primality, SetBits, SmallFactorsSieveFactory, equals_primitive, lucas_test,
+        AStarBase, CongruenceSieveFactory, CunninghamSieveFactory, JoyePaillierFactory, LucasCheck, MillerRabin,
+        Primality, RangeSieve, RangeSieveFactory, RejectionSamplingFactory, SetBits, SmallFactorsSieveFactory,
+        equals_primitive, lucas_test_ct, miller_rabin_ct, try_lucas_test,
     },
 };
 
 /// The specific category of primes.
+///
+/// New flavors may be added in the future, so matching on this enum requires a wildcard arm.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Flavor {
     /// Any prime.
     Any,
     /// Safe prime, that is a prime `x` such that `(x - 1) / 2` is also prime.
     Safe,
+    /// Blum prime, that is a prime `x` such that `x = 3 mod 4`.
+    ///
+    /// A product of two such primes is a Blum integer, as used in Rabin and Paillier-Blum moduli.
+    Blum,
+}
+
+/// The kind of a Cunningham chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CunninghamKind {
+    /// A chain of the first kind, where each next element is `2p + 1`.
+    ///
+    /// Every element of such a chain except the first one is a safe prime.
+    First,
+    /// A chain of the second kind, where each next element is `2p - 1`.
+    Second,
 }
 
 /// Returns a random prime of size `bit_length` using the provided RNG.
 ///
 /// The returned prime will have its MSB set.
 ///
-/// Panics if `bit_length` is less than the bit length of the smallest possible prime with the requested `flavor`.
+/// Panics if `bit_length` is less than the bit length of the smallest possible prime with the requested `flavor`,
+/// or greater than the precision of `T` (for fixed-size integers); see [`try_random_prime`] for a fallible version.
 ///
 /// See [`is_prime`] for details about the performed checks.
 pub fn random_prime<T, R>(rng: &mut R, flavor: Flavor, bit_length: u32) -> T
@@ -36,13 +62,395 @@ where
         .expect("will produce a result eventually")
 }
 
+/// Same as [`random_prime`], but returns an error instead of panicking:
+/// [`Error::BitLengthTooSmall`] or [`Error::BitLengthTooLarge`] if `bit_length` is out of range.
+pub fn try_random_prime<T, R>(rng: &mut R, flavor: Flavor, bit_length: u32) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    try_random_prime_with_budget(rng, flavor, bit_length, Budget::unlimited())
+}
+
+/// Same as [`try_random_prime`], but returns [`Error::AttemptsExhausted`]
+/// if the limits set by `budget` were reached.
+pub fn try_random_prime_with_budget<T, R>(
+    rng: &mut R,
+    flavor: Flavor,
+    bit_length: u32,
+    budget: Budget<'_>,
+) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let factory = SmallFactorsSieveFactory::new(flavor, bit_length, SetBits::Msb)?;
+    sieve_and_find_with_budget(rng, factory, |_rng, candidate| is_prime(flavor, candidate), budget)?
+        .ok_or(Error::NoPrimesInRange { flavor })
+}
+
+/// Returns a random prime of size `bit_length` using the provided RNG,
+/// uniformly distributed among the primes of the requested `flavor` with the MSB set.
+///
+/// Unlike [`random_prime`], every candidate is sampled independently
+/// (see [`RejectionSamplingFactory`](`crate::hazmat::RejectionSamplingFactory`)),
+/// so the result does not depend on the gaps between primes. This is slower and consumes more randomness.
+/// For a faster method with a negligible deviation from the uniform distribution, see
+/// [`FouqueTibouchiFactory`](`crate::hazmat::FouqueTibouchiFactory`).
+///
+/// Panics if `bit_length` is less than the bit length of the smallest possible prime with the requested `flavor`,
+/// or greater than the precision of `T` (for fixed-size integers);
+/// see [`try_random_prime_uniform`] for a fallible version.
+///
+/// See [`is_prime`] for details about the performed checks.
+pub fn random_prime_uniform<T, R>(rng: &mut R, flavor: Flavor, bit_length: u32) -> T
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let factory = RejectionSamplingFactory::new(flavor, bit_length, SetBits::Msb)
+        .unwrap_or_else(|err| panic!("Error creating the sieve: {err}"));
+    sieve_and_find(rng, factory, |_rng, candidate| is_prime(flavor, candidate))
+        .unwrap_or_else(|err| panic!("Error generating random candidates: {err}"))
+        .expect("will produce a result eventually")
+}
+
+/// Same as [`random_prime_uniform`], but returns an error instead of panicking:
+/// [`Error::BitLengthTooSmall`] or [`Error::BitLengthTooLarge`] if `bit_length` is out of range.
+pub fn try_random_prime_uniform<T, R>(rng: &mut R, flavor: Flavor, bit_length: u32) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    try_random_prime_uniform_with_budget(rng, flavor, bit_length, Budget::unlimited())
+}
+
+/// Same as [`try_random_prime_uniform`], but returns [`Error::AttemptsExhausted`]
+/// if the limits set by `budget` were reached.
+pub fn try_random_prime_uniform_with_budget<T, R>(
+    rng: &mut R,
+    flavor: Flavor,
+    bit_length: u32,
+    budget: Budget<'_>,
+) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let factory = RejectionSamplingFactory::new(flavor, bit_length, SetBits::Msb)?;
+    sieve_and_find_with_budget(rng, factory, |_rng, candidate| is_prime(flavor, candidate), budget)?
+        .ok_or(Error::NoPrimesInRange { flavor })
+}
+
+/// Returns a random prime of size `bit_length` using the provided RNG,
+/// with the candidates generated and tested without branching on their values.
+///
+/// The candidates are produced by [`JoyePaillierFactory`](`crate::hazmat::JoyePaillierFactory`)
+/// and checked with [`is_prime_ct`], so the running time only reveals the number of candidates tested.
+/// This is intended for settings where timing side channels are a concern; it is considerably slower
+/// than [`random_prime`].
+///
+/// Panics if `bit_length` is less than 6, or greater than the precision of `Uint<LIMBS>`;
+/// see [`try_random_prime_ct`] for a fallible version.
+pub fn random_prime_ct<const LIMBS: usize, R>(rng: &mut R, bit_length: u32) -> Uint<LIMBS>
+where
+    R: CryptoRng + ?Sized,
+{
+    let factory = JoyePaillierFactory::new(bit_length).unwrap_or_else(|err| panic!("Error creating the sieve: {err}"));
+    sieve_and_find(rng, factory, |_rng, candidate| is_prime_ct(candidate).into())
+        .unwrap_or_else(|err| panic!("Error generating random candidates: {err}"))
+        .expect("will produce a result eventually")
+}
+
+/// Same as [`random_prime_ct`], but returns an error instead of panicking:
+/// [`Error::BitLengthTooSmall`] or [`Error::BitLengthTooLarge`] if `bit_length` is out of range.
+pub fn try_random_prime_ct<const LIMBS: usize, R>(rng: &mut R, bit_length: u32) -> Result<Uint<LIMBS>, Error>
+where
+    R: CryptoRng + ?Sized,
+{
+    try_random_prime_ct_with_budget(rng, bit_length, Budget::unlimited())
+}
+
+/// Same as [`try_random_prime_ct`], but returns [`Error::AttemptsExhausted`]
+/// if the limits set by `budget` were reached.
+pub fn try_random_prime_ct_with_budget<const LIMBS: usize, R>(
+    rng: &mut R,
+    bit_length: u32,
+    budget: Budget<'_>,
+) -> Result<Uint<LIMBS>, Error>
+where
+    R: CryptoRng + ?Sized,
+{
+    let factory = JoyePaillierFactory::new(bit_length)?;
+    sieve_and_find_with_budget(rng, factory, |_rng, candidate| is_prime_ct(candidate).into(), budget)?
+        .ok_or(Error::NoPrimesInRange { flavor: Flavor::Any })
+}
+
+/// Returns a random prime of size `bit_length` equal to `residue` modulo `modulus` using the provided RNG.
+///
+/// The returned prime will have its MSB set.
+///
+/// This is useful, for example, for NTT-friendly moduli (`p = 1 mod 2^k`),
+/// or Diffie-Hellman groups with a fixed generator (e.g. `p = 7 mod 8` for the generator 2).
+///
+/// Panics if `bit_length` is less than 2, if `modulus` is zero or `lcm(2, modulus)` is not smaller than
+/// `2^(bit_length - 1)`, if `residue` is not smaller than and coprime with `modulus`,
+/// or if there are no primes of size `bit_length` in the residue class;
+/// see [`try_random_prime_congruent`] for a fallible version.
+///
+/// See [`is_prime`] for details about the performed checks.
+pub fn random_prime_congruent<T, R>(rng: &mut R, bit_length: u32, residue: &T, modulus: &T) -> T
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let factory = CongruenceSieveFactory::new(bit_length, residue.clone(), modulus.clone())
+        .unwrap_or_else(|err| panic!("Error creating the sieve: {err}"));
+    sieve_and_find(rng, factory, |_rng, candidate| is_prime(Flavor::Any, candidate))
+        .unwrap_or_else(|err| panic!("Error generating random candidates: {err}"))
+        .unwrap_or_else(|| panic!("{}", Error::NoPrimesInRange { flavor: Flavor::Any }))
+}
+
+/// Same as [`random_prime_congruent`], but returns an error instead of panicking
+/// if the parameters are out of range (see [`CongruenceSieveFactory::new`]),
+/// or [`Error::NoPrimesInRange`] if there are no primes of size `bit_length` in the residue class.
+pub fn try_random_prime_congruent<T, R>(rng: &mut R, bit_length: u32, residue: &T, modulus: &T) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    try_random_prime_congruent_with_budget(rng, bit_length, residue, modulus, Budget::unlimited())
+}
+
+/// Same as [`try_random_prime_congruent`], but returns [`Error::AttemptsExhausted`]
+/// if the limits set by `budget` were reached.
+pub fn try_random_prime_congruent_with_budget<T, R>(
+    rng: &mut R,
+    bit_length: u32,
+    residue: &T,
+    modulus: &T,
+    budget: Budget<'_>,
+) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let factory = CongruenceSieveFactory::new(bit_length, residue.clone(), modulus.clone())?;
+    sieve_and_find_with_budget(rng, factory, |_rng, candidate| is_prime(Flavor::Any, candidate), budget)?
+        .ok_or(Error::NoPrimesInRange { flavor: Flavor::Any })
+}
+
+/// Returns a random prime of the given `flavor` in the interval `[min, max]` (inclusive) using the provided RNG.
+///
+/// The search starts at a uniformly random point of the interval and wraps around at `max`,
+/// so the whole interval is covered.
+///
+/// Returns an error if `min > max`, or if the interval contains no primes of the requested `flavor`.
+///
+/// See [`is_prime`] for details about the performed checks.
+pub fn random_prime_in_range<T, R>(rng: &mut R, flavor: Flavor, min: &T, max: &T) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    random_prime_in_range_with_budget(rng, flavor, min, max, Budget::unlimited())
+}
+
+/// Same as [`random_prime_in_range`], but returns [`Error::AttemptsExhausted`]
+/// if the limits set by `budget` were reached before a prime was found.
+pub fn random_prime_in_range_with_budget<T, R>(
+    rng: &mut R,
+    flavor: Flavor,
+    min: &T,
+    max: &T,
+    budget: Budget<'_>,
+) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let factory = RangeSieveFactory::new(flavor, min.clone(), max.clone())?;
+    sieve_and_find_with_budget(rng, factory, |_rng, candidate| is_prime(flavor, candidate), budget)?
+        .ok_or(Error::NoPrimesInRange { flavor })
+}
+
+/// An iterator over the primes of the given flavor in the interval `[min, max]` (inclusive), in increasing order.
+///
+/// The candidates are produced by a [`RangeSieve`] (skipping multiples of small primes),
+/// and checked with [`is_prime`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrimesInRange<T: Unsigned> {
+    flavor: Flavor,
+    sieve: RangeSieve<T>,
+}
+
+impl<T> PrimesInRange<T>
+where
+    T: Unsigned + RandomMod,
+{
+    /// Creates a new iterator over the primes of the given `flavor` in the interval `[min, max]`.
+    ///
+    /// The iterator produces nothing if `max < min`.
+    pub fn new(flavor: Flavor, min: &T, max: &T) -> Result<Self, Error> {
+        Ok(Self {
+            flavor,
+            sieve: RangeSieve::new(min.clone(), max.clone(), flavor)?,
+        })
+    }
+}
+
+impl<T> Iterator for PrimesInRange<T>
+where
+    T: Unsigned + RandomMod,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let flavor = self.flavor;
+        self.sieve.find(|candidate| is_prime(flavor, candidate))
+    }
+}
+
+/// Returns the smallest prime of the given `flavor` that is greater than or equal to `num`.
+///
+/// Returns an error if there are no such primes within the precision of `num`
+/// (in particular, the search never wraps around).
+///
+/// See [`is_prime`] for details about the performed checks.
+pub fn next_prime<T>(flavor: Flavor, num: &T) -> Result<T, Error>
+where
+    T: Unsigned + RandomMod,
+{
+    // The largest number fitting in the precision of `num`.
+    let max = T::zero_like(num).wrapping_sub(&T::one_like(num));
+    RangeSieve::new(num.clone(), max, flavor)?
+        .find(|candidate| is_prime(flavor, candidate))
+        .ok_or(Error::NoPrimesInRange { flavor })
+}
+
+/// Returns the largest prime of the given `flavor` that is less than or equal to `num`.
+///
+/// Returns an error if there are no such primes (that is, `num` is less than the smallest prime of that `flavor`).
+///
+/// See [`is_prime`] for details about the performed checks.
+pub fn prev_prime<T>(flavor: Flavor, num: &T) -> Result<T, Error>
+where
+    T: Unsigned + RandomMod,
+{
+    RangeSieve::new_decreasing(T::zero_like(num), num.clone(), flavor)
+        .find(|candidate| is_prime(flavor, candidate))
+        .ok_or(Error::NoPrimesInRange { flavor })
+}
+
+/// Returns the first element of a random Cunningham chain of the given `kind` and `length`,
+/// of size `bit_length`, using the provided RNG.
+///
+/// The returned number will have its MSB set, and the chain may continue beyond `length`.
+/// Only odd starting elements are considered.
+///
+/// Note that long chains are rare: the expected running time grows exponentially with `length`,
+/// and if no chain of the requested length exists among the numbers of size `bit_length`,
+/// this function will never return.
+///
+/// Panics if `bit_length` is less than 2, if `length` is zero,
+/// or if the last element of the chain (which has up to `bit_length + length - 1` bits)
+/// does not fit in the precision of `T` (for fixed-size integers);
+/// see [`try_random_cunningham_chain`] for a fallible version.
+///
+/// See [`is_cunningham_chain`] for details about the performed checks.
+pub fn random_cunningham_chain<T, R>(rng: &mut R, kind: CunninghamKind, length: usize, bit_length: u32) -> T
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let factory = CunninghamSieveFactory::new(kind, length, bit_length)
+        .unwrap_or_else(|err| panic!("Error creating the sieve: {err}"));
+    sieve_and_find(rng, factory, |_rng, candidate| {
+        is_cunningham_chain(kind, length, candidate)
+    })
+    .unwrap_or_else(|err| panic!("Error generating random candidates: {err}"))
+    .expect("will produce a result eventually")
+}
+
+/// Same as [`random_cunningham_chain`], but returns an error instead of panicking
+/// if the parameters are out of range (see [`CunninghamSieveFactory::new`]).
+pub fn try_random_cunningham_chain<T, R>(
+    rng: &mut R,
+    kind: CunninghamKind,
+    length: usize,
+    bit_length: u32,
+) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    try_random_cunningham_chain_with_budget(rng, kind, length, bit_length, Budget::unlimited())
+}
+
+/// Same as [`try_random_cunningham_chain`], but returns [`Error::AttemptsExhausted`]
+/// if the limits set by `budget` were reached.
+pub fn try_random_cunningham_chain_with_budget<T, R>(
+    rng: &mut R,
+    kind: CunninghamKind,
+    length: usize,
+    bit_length: u32,
+    budget: Budget<'_>,
+) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let factory = CunninghamSieveFactory::new(kind, length, bit_length)?;
+    sieve_and_find_with_budget(
+        rng,
+        factory,
+        |_rng, candidate| is_cunningham_chain(kind, length, candidate),
+        budget,
+    )?
+    .ok_or(Error::NoPrimesInRange { flavor: Flavor::Any })
+}
+
+/// Checks if the first `length` elements of the Cunningham chain of the given `kind` starting from `start`
+/// are all prime.
+///
+/// Returns `false` if any of the elements does not fit in the precision of `T`.
+/// The chain may continue beyond `length` elements; this is not checked.
+///
+/// See [`is_prime`] for details about the performed checks.
+pub fn is_cunningham_chain<T>(kind: CunninghamKind, length: usize, start: &T) -> bool
+where
+    T: Unsigned + RandomMod,
+{
+    let mut element = start.clone();
+    for i in 0..length {
+        if !is_prime(Flavor::Any, &element) {
+            return false;
+        }
+        if i + 1 == length {
+            break;
+        }
+        let doubled = match element.checked_add(&element).into_option() {
+            Some(doubled) => doubled,
+            None => return false,
+        };
+        element = match kind {
+            CunninghamKind::First => match doubled.checked_add(&T::one_like(&doubled)).into_option() {
+                Some(next) => next,
+                None => return false,
+            },
+            CunninghamKind::Second => doubled.wrapping_sub(&T::one_like(&doubled)),
+        };
+    }
+    true
+}
+
 /// Checks if the given number is prime.
 ///
 /// Performed tests:
 /// - Miller-Rabin test with base 2;
 /// - [`LucasCheck::Bpsw21`] test with [`AStarBase`].
 ///
-/// See [`MillerRabin`] and [`lucas_test`] for more details about the tests.
+/// See [`MillerRabin`] and [`lucas_test`](`crate::hazmat::lucas_test`) for more details about the tests.
 ///
 /// This is the recommended approach by Baillie et al[^Baillie2021],
 /// improving on the BPSW'80 test[^Baillie1980].
@@ -67,6 +475,7 @@ where
     match flavor {
         Flavor::Any => {}
         Flavor::Safe => return is_safe_prime(candidate),
+        Flavor::Blum => return is_blum_prime(candidate),
     }
 
     if equals_primitive(candidate, 1) {
@@ -88,13 +497,46 @@ where
         return false;
     }
 
-    match lucas_test(odd_candidate, AStarBase, LucasCheck::Bpsw21) {
-        Primality::Composite => false,
-        Primality::Prime => true,
-        Primality::ProbablyPrime => true,
+    // Failing to find a Lucas base is not known to happen; if it does, err on the side of rejecting the candidate.
+    match try_lucas_test(odd_candidate, AStarBase, LucasCheck::Bpsw21) {
+        Ok(Primality::Composite) | Err(_) => false,
+        Ok(Primality::Prime) => true,
+        Ok(Primality::ProbablyPrime) => true,
     }
 }
 
+/// Checks if the given number is prime in constant time.
+///
+/// Performed tests:
+/// - Miller-Rabin test with base 2, see [`miller_rabin_ct`](`crate::hazmat::miller_rabin_ct`);
+/// - strong Lucas test with [`SelfridgeBase`](`crate::hazmat::SelfridgeBase`),
+///   see [`lucas_test_ct`](`crate::hazmat::lucas_test_ct`).
+///
+/// This is the original BPSW test[^Baillie1980], which, like the one used by [`is_prime`],
+/// has no known pseudoprimes.
+/// The running time only depends on the precision of `Uint<LIMBS>`, and not on the value of `candidate`,
+/// so it is suitable for testing secret candidates.
+/// It is several times slower than [`is_prime`].
+///
+/// [^Baillie1980]: R. Baillie, S. S. Wagstaff, "Lucas pseudoprimes",
+///       Math. Comp. 35 1391-1417 (1980),
+///       DOI: [10.2307/2006406](https://dx.doi.org/10.2307/2006406),
+///       <http://mpqs.free.fr/LucasPseudoprimes.pdf>
+pub fn is_prime_ct<const LIMBS: usize>(candidate: &Uint<LIMBS>) -> Choice {
+    let is_two = candidate.ct_eq(&Uint::from_u32(2));
+
+    // The tests require an odd candidate greater than 1; for other values substitute a dummy one
+    // to keep the running time the same, and discard the result.
+    let is_testable = Choice::from(candidate.bit(0)) & !candidate.ct_eq(&Uint::ONE);
+    let odd_candidate = Uint::conditional_select(&Uint::from_u32(3), candidate, is_testable);
+    let odd_candidate = Odd::new(odd_candidate).expect("the candidate is odd by construction");
+
+    let mr = miller_rabin_ct(&odd_candidate, &Uint::from_u32(2));
+    let lucas = lucas_test_ct(&odd_candidate);
+
+    is_two | (is_testable & mr & lucas)
+}
+
 /// Checks if the given number is a safe prime.
 ///
 /// See [`is_prime`] for details about the performed checks.
@@ -119,15 +561,33 @@ where
     is_prime(Flavor::Any, candidate) && is_prime(Flavor::Any, &candidate.wrapping_shr_vartime(1))
 }
 
+/// Checks if the given number is a Blum prime.
+///
+/// See [`is_prime`] for details about the performed checks.
+fn is_blum_prime<T>(candidate: &T) -> bool
+where
+    T: Unsigned + RandomMod,
+{
+    candidate.as_ref()[0].0 & 3 == 3 && is_prime(Flavor::Any, candidate)
+}
+
 #[cfg(test)]
 mod tests {
-    use crypto_bigint::{BoxedUint, CheckedAdd, RandomMod, U64, U128, Uint, Unsigned, Word, nlimbs};
+    use alloc::vec::Vec;
+
+    use crypto_bigint::{BoxedUint, CheckedAdd, NonZero, RandomMod, U64, U128, Uint, Unsigned, Word, nlimbs};
     use num_prime::nt_funcs::is_prime64;
 
-    use super::{Flavor, is_prime, random_prime};
+    use super::{
+        CunninghamKind, Flavor, PrimesInRange, is_cunningham_chain, is_prime, is_prime_ct, next_prime, prev_prime,
+        random_cunningham_chain, random_prime, random_prime_congruent, random_prime_ct, random_prime_in_range,
+        random_prime_in_range_with_budget, random_prime_uniform, try_random_cunningham_chain,
+        try_random_cunningham_chain_with_budget, try_random_prime, try_random_prime_congruent, try_random_prime_ct,
+        try_random_prime_uniform, try_random_prime_with_budget,
+    };
     use crate::{
-        fips,
-        hazmat::{minimum_mr_iterations, primes, pseudoprimes},
+        Budget, Error, fips,
+        hazmat::{estimate_primecount, minimum_mr_iterations, primes, pseudoprimes},
     };
 
     fn fips_is_prime<T: Unsigned + RandomMod>(flavor: Flavor, num: &T) -> bool {
@@ -140,6 +600,7 @@ mod tests {
         for num in nums {
             assert!(is_prime(Flavor::Any, num));
             assert!(fips_is_prime(Flavor::Any, num));
+            assert!(bool::from(is_prime_ct(num)));
         }
     }
 
@@ -156,6 +617,7 @@ mod tests {
         for num in nums {
             assert!(!is_prime(Flavor::Any, &U64::from(*num)));
             assert!(!fips_is_prime(Flavor::Any, &U64::from(*num)));
+            assert!(!bool::from(is_prime_ct(&U64::from(*num))));
         }
     }
 
@@ -176,6 +638,18 @@ mod tests {
 
         assert!(!is_prime(Flavor::Any, &pseudoprimes::LARGE_CARMICHAEL_NUMBER));
         assert!(!fips_is_prime(Flavor::Any, &pseudoprimes::LARGE_CARMICHAEL_NUMBER));
+        assert!(!bool::from(is_prime_ct(&pseudoprimes::LARGE_CARMICHAEL_NUMBER)));
+    }
+
+    #[test]
+    fn small_numbers_ct() {
+        for num in 0..2000u32 {
+            assert_eq!(
+                bool::from(is_prime_ct(&U64::from(num))),
+                is_prime_ref(num.into()),
+                "num = {num}"
+            );
+        }
     }
 
     fn test_cunningham_chain<const L: usize>(length: usize, num: &Uint<L>) {
@@ -196,6 +670,9 @@ mod tests {
         // The chain ended.
         assert!(!is_prime(Flavor::Any, &next));
         assert!(!fips_is_prime(Flavor::Any, &next));
+
+        assert!(is_cunningham_chain(CunninghamKind::First, length, num));
+        assert!(!is_cunningham_chain(CunninghamKind::First, length + 1, num));
     }
 
     #[test]
@@ -208,6 +685,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cunningham_chains_second_kind() {
+        // The smallest chain of the second kind of length 5.
+        let num = U64::from(1531u32);
+        assert!(is_cunningham_chain(CunninghamKind::Second, 5, &num));
+        assert!(!is_cunningham_chain(CunninghamKind::Second, 6, &num));
+        assert!(!is_cunningham_chain(CunninghamKind::First, 2, &num));
+
+        // The chain does not fit in the precision.
+        let num = U64::from(u64::MAX >> 1);
+        assert!(!is_cunningham_chain(CunninghamKind::First, 2, &num));
+    }
+
+    #[test]
+    fn cunningham_chain_generation() {
+        let mut rng = rand::rng();
+        for kind in [CunninghamKind::First, CunninghamKind::Second] {
+            for length in 1..=4 {
+                let p: U128 = random_cunningham_chain(&mut rng, kind, length, 64);
+                assert!(p.bits_vartime() == 64);
+                assert!(is_cunningham_chain(kind, length, &p));
+            }
+
+            // Checks that the chain fits in the precision.
+            let p: BoxedUint = random_cunningham_chain(&mut rng, kind, 3, 128);
+            assert!(p.bits_vartime() == 128);
+            assert!(is_cunningham_chain(kind, 3, &p));
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Error creating the sieve: The requested bit length of the candidate (129) is larger than the maximum size of the target integer type (128)."
+    )]
+    fn cunningham_chain_too_long() {
+        let mut rng = rand::rng();
+        let _p: U128 = random_cunningham_chain(&mut rng, CunninghamKind::First, 3, 127);
+    }
+
     #[test]
     fn prime_generation() {
         let mut rng = rand::rng();
@@ -258,11 +774,330 @@ mod tests {
         }
     }
 
+    #[test]
+    fn blum_prime_generation() {
+        let mut rng = rand::rng();
+
+        for bit_length in (28..=128).step_by(10) {
+            let p: U128 = random_prime(&mut rng, Flavor::Blum, bit_length);
+            assert!(p.bits_vartime() == bit_length);
+            assert!(p.as_words()[0] & 3 == 3);
+            assert!(is_prime(Flavor::Blum, &p));
+            assert!(fips_is_prime(Flavor::Blum, &p));
+        }
+    }
+
+    #[test]
+    fn blum_prime_generation_boxed() {
+        let mut rng = rand::rng();
+
+        for bit_length in (28..=128).step_by(10) {
+            let p: BoxedUint = random_prime(&mut rng, Flavor::Blum, bit_length);
+            assert!(p.bits_vartime() == bit_length);
+            assert!(p.to_words().len() == nlimbs!(bit_length));
+            assert!(is_prime(Flavor::Blum, &p));
+            assert!(fips_is_prime(Flavor::Blum, &p));
+        }
+    }
+
+    #[test]
+    fn congruent_prime_generation() {
+        let mut rng = rand::rng();
+
+        // NTT-friendly primes
+        let modulus = U128::ONE.wrapping_shl_vartime(20);
+        for bit_length in (28..=128).step_by(10) {
+            let p: U128 = random_prime_congruent(&mut rng, bit_length, &U128::ONE, &modulus);
+            assert!(p.bits_vartime() == bit_length);
+            assert!(p.as_words()[0] & ((1 << 20) - 1) == 1);
+            assert!(is_prime(Flavor::Any, &p));
+        }
+
+        // Odd modulus
+        let modulus = U128::from(15u32);
+        for bit_length in (28..=128).step_by(10) {
+            let p: U128 = random_prime_congruent(&mut rng, bit_length, &U128::from(4u32), &modulus);
+            assert!(p.bits_vartime() == bit_length);
+            assert!(p % NonZero::new(modulus).unwrap() == U128::from(4u32));
+            assert!(is_prime(Flavor::Any, &p));
+        }
+    }
+
+    #[test]
+    fn congruent_prime_generation_boxed() {
+        let mut rng = rand::rng();
+
+        for bit_length in (28..=128).step_by(10) {
+            let p: BoxedUint =
+                random_prime_congruent(&mut rng, bit_length, &BoxedUint::from(7u32), &BoxedUint::from(8u32));
+            assert!(p.bits_vartime() == bit_length);
+            assert!(p.to_words().len() == nlimbs!(bit_length));
+            assert!(p.as_words()[0] & 7 == 7);
+            assert!(is_prime(Flavor::Any, &p));
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Error creating the sieve: The parameter `residue` is outside of the range allowed by the algorithm."
+    )]
+    fn congruent_prime_not_coprime() {
+        let mut rng = rand::rng();
+        let _p: U64 = random_prime_congruent(&mut rng, 64, &U64::from(6u32), &U64::from(8u32));
+    }
+
+    #[test]
+    fn congruent_prime_empty_class() {
+        // The only 6-bit numbers equal to 1 modulo 16 are 33 and 49, neither of which is prime.
+        let mut rng = rand::rng();
+        assert_eq!(
+            try_random_prime_congruent(&mut rng, 6, &U64::ONE, &U64::from(16u32)),
+            Err(Error::NoPrimesInRange { flavor: Flavor::Any })
+        );
+        assert_eq!(
+            try_random_prime_congruent(&mut rng, 6, &BoxedUint::one(), &BoxedUint::from(16u32)),
+            Err(Error::NoPrimesInRange { flavor: Flavor::Any })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The requested range does not contain primes of the flavor Any.")]
+    fn congruent_prime_empty_class_panics() {
+        let mut rng = rand::rng();
+        let _p: U64 = random_prime_congruent(&mut rng, 6, &U64::ONE, &U64::from(16u32));
+    }
+
+    #[test]
+    fn uniform_prime_generation() {
+        let mut rng = rand::rng();
+        for flavor in [Flavor::Any, Flavor::Safe, Flavor::Blum] {
+            for bit_length in (28..=128).step_by(10) {
+                let p: U128 = random_prime_uniform(&mut rng, flavor, bit_length);
+                assert!(p.bits_vartime() == bit_length);
+                assert!(is_prime(flavor, &p));
+            }
+        }
+
+        let p: BoxedUint = random_prime_uniform(&mut rng, Flavor::Any, 200);
+        assert!(p.bits_vartime() == 200);
+        assert!(is_prime(Flavor::Any, &p));
+    }
+
+    #[test]
+    fn ct_prime_generation() {
+        let mut rng = rand::rng();
+        for bit_length in (8..=128).step_by(10) {
+            let p: U128 = random_prime_ct(&mut rng, bit_length);
+            assert!(p.bits_vartime() == bit_length);
+            assert!(is_prime(Flavor::Any, &p));
+        }
+    }
+
+    #[test]
+    fn prime_in_range_generation() {
+        let mut rng = rand::rng();
+
+        let min = U128::from(1000u32);
+        let max = U128::ONE.wrapping_shl_vartime(100).wrapping_add(&U128::from(12345u32));
+        for flavor in [Flavor::Any, Flavor::Safe, Flavor::Blum] {
+            for _ in 0..10 {
+                let p = random_prime_in_range(&mut rng, flavor, &min, &max).unwrap();
+                assert!(p >= min && p <= max);
+                assert!(is_prime(flavor, &p));
+            }
+        }
+
+        // A narrow range containing a single prime
+        let p = random_prime_in_range(&mut rng, Flavor::Any, &U128::from(1328u32), &U128::from(1361u32)).unwrap();
+        assert_eq!(p, U128::from(1361u32));
+        let p = random_prime_in_range(&mut rng, Flavor::Any, &U128::from(2u32), &U128::from(2u32)).unwrap();
+        assert_eq!(p, U128::from(2u32));
+    }
+
+    #[test]
+    fn prime_in_range_generation_boxed() {
+        let mut rng = rand::rng();
+
+        let min = BoxedUint::from(1u64 << 60);
+        let max = BoxedUint::one_with_precision(128).wrapping_shl_vartime(100);
+        for _ in 0..10 {
+            let p = random_prime_in_range(&mut rng, Flavor::Any, &min, &max).unwrap();
+            assert!(p >= min && p <= max);
+            assert!(is_prime(Flavor::Any, &p));
+        }
+    }
+
+    #[test]
+    fn prime_in_range_errors() {
+        let mut rng = rand::rng();
+        assert_eq!(
+            random_prime_in_range(&mut rng, Flavor::Any, &U64::from(1330u32), &U64::from(1360u32)),
+            Err(Error::NoPrimesInRange { flavor: Flavor::Any })
+        );
+        assert_eq!(
+            random_prime_in_range(&mut rng, Flavor::Safe, &U64::from(24u32), &U64::from(46u32)),
+            Err(Error::NoPrimesInRange { flavor: Flavor::Safe })
+        );
+        assert_eq!(
+            random_prime_in_range(&mut rng, Flavor::Any, &U64::from(2u32), &U64::ONE),
+            Err(Error::InvalidParameter { name: "min" })
+        );
+    }
+
+    fn is_flavored_prime_ref(flavor: Flavor, num: u64) -> bool {
+        match flavor {
+            Flavor::Any => is_prime64(num),
+            Flavor::Safe => is_prime64(num) && is_prime64(num >> 1),
+            Flavor::Blum => is_prime64(num) && num % 4 == 3,
+        }
+    }
+
+    fn next_prime_ref(flavor: Flavor, num: u64) -> Option<u64> {
+        (num..).take(10000).find(|x| is_flavored_prime_ref(flavor, *x))
+    }
+
+    fn prev_prime_ref(flavor: Flavor, num: u64) -> Option<u64> {
+        (0..=num).rev().find(|x| is_flavored_prime_ref(flavor, *x))
+    }
+
+    #[test]
+    fn next_and_prev_prime() {
+        for flavor in [Flavor::Any, Flavor::Safe, Flavor::Blum] {
+            for num in (0u64..200).chain(100_000..100_020).chain(1_000_000_000..1_000_000_010) {
+                let next = next_prime(flavor, &U64::from(num)).map(u64::from);
+                assert_eq!(next.ok(), next_prime_ref(flavor, num));
+                let prev = prev_prime(flavor, &U64::from(num)).map(u64::from);
+                assert_eq!(prev.ok(), prev_prime_ref(flavor, num));
+            }
+        }
+
+        // Large gaps
+        let num = U64::from(1u64 << 40);
+        let prev = prev_prime(Flavor::Safe, &num).unwrap();
+        assert!(is_prime(Flavor::Safe, &prev));
+        assert!(next_prime(Flavor::Safe, &prev.wrapping_add(&U64::ONE)).unwrap() > num);
+    }
+
+    #[test]
+    fn prev_prime_over_a_range() {
+        for flavor in [Flavor::Any, Flavor::Safe, Flavor::Blum] {
+            for (start, len) in [(0, 10_000), (1u64 << 40, 1000), (u64::MAX - 1000, 1000)] {
+                let mut expected = prev_prime_ref(flavor, start);
+                for num in start..=start + len {
+                    if is_flavored_prime_ref(flavor, num) {
+                        expected = Some(num);
+                    }
+                    assert_eq!(prev_prime(flavor, &U64::from(num)).map(u64::from).ok(), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn next_and_prev_prime_bounds() {
+        // The largest 64-bit prime is `2^64 - 59`
+        let largest = U64::from(u64::MAX - 58);
+        assert_eq!(prev_prime(Flavor::Any, &U64::MAX).unwrap(), largest);
+        assert_eq!(next_prime(Flavor::Any, &largest).unwrap(), largest);
+        assert_eq!(
+            next_prime(Flavor::Any, &largest.wrapping_add(&U64::ONE)),
+            Err(Error::NoPrimesInRange { flavor: Flavor::Any })
+        );
+        assert_eq!(
+            next_prime(Flavor::Safe, &U64::MAX),
+            Err(Error::NoPrimesInRange { flavor: Flavor::Safe })
+        );
+
+        assert_eq!(
+            prev_prime(Flavor::Any, &U64::ONE),
+            Err(Error::NoPrimesInRange { flavor: Flavor::Any })
+        );
+        assert_eq!(
+            prev_prime(Flavor::Safe, &U64::from(4u32)),
+            Err(Error::NoPrimesInRange { flavor: Flavor::Safe })
+        );
+        assert_eq!(
+            prev_prime(Flavor::Blum, &U64::from(2u32)),
+            Err(Error::NoPrimesInRange { flavor: Flavor::Blum })
+        );
+    }
+
+    #[test]
+    fn next_and_prev_prime_boxed() {
+        let num = BoxedUint::one_with_precision(256).wrapping_shl_vartime(200);
+        let next = next_prime(Flavor::Any, &num).unwrap();
+        let prev = prev_prime(Flavor::Any, &num).unwrap();
+        assert!(prev < num && next > num);
+        assert!(is_prime(Flavor::Any, &next) && is_prime(Flavor::Any, &prev));
+        assert_eq!(
+            prev_prime(Flavor::Any, &next.wrapping_sub(&BoxedUint::one())).unwrap(),
+            prev
+        );
+    }
+
+    #[test]
+    fn primes_in_range() {
+        for flavor in [Flavor::Any, Flavor::Safe, Flavor::Blum] {
+            for (min, max) in [(0u64, 1000u64), (5, 5), (6, 6), (100_000, 101_000), (10, 9)] {
+                let primes = PrimesInRange::new(flavor, &U64::from(min), &U64::from(max))
+                    .unwrap()
+                    .map(u64::from)
+                    .collect::<Vec<_>>();
+                let reference = (min..=max)
+                    .filter(|x| match flavor {
+                        Flavor::Any => is_prime64(*x),
+                        Flavor::Safe => is_prime64(*x) && is_prime64(*x / 2),
+                        Flavor::Blum => is_prime64(*x) && *x % 4 == 3,
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(primes, reference);
+            }
+        }
+
+        // The end of the precision range
+        let primes = PrimesInRange::new(Flavor::Any, &U64::from(u64::MAX - 100), &U64::MAX)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(primes.last(), Some(&U64::from(u64::MAX - 58)));
+
+        let min = BoxedUint::one_with_precision(128).wrapping_shl_vartime(100);
+        let max = min.wrapping_add(&BoxedUint::from(1000u32));
+        for p in PrimesInRange::new(Flavor::Any, &min, &max).unwrap() {
+            assert!(p >= min && p <= max);
+            assert!(is_prime(Flavor::Any, &p));
+        }
+    }
+
+    #[test]
+    fn primes_in_range_count() {
+        // pi(10^5) = 9592
+        let count = PrimesInRange::new(Flavor::Any, &U64::ZERO, &U64::from(100_000u32))
+            .unwrap()
+            .count();
+        assert_eq!(count, 9592);
+
+        // The window starting at 2 is sieved too: all the candidates passed to `is_prime()` are prime.
+        let candidates = PrimesInRange::new(Flavor::Any, &U64::from(2u32), &U64::from(100_000u32))
+            .unwrap()
+            .sieve
+            .count();
+        assert_eq!(candidates, 9592);
+
+        // Compare with the estimate in a small window.
+        let min = U64::from(1u64 << 40);
+        let max = min.wrapping_add(&U64::from(100_000u32));
+        let count = PrimesInRange::new(Flavor::Any, &min, &max).unwrap().count() as u64;
+        let estimate =
+            u64::from(estimate_primecount(&max)) - u64::from(estimate_primecount(&min.wrapping_sub(&U64::ONE)));
+        assert!(count.abs_diff(estimate) < estimate / 10);
+    }
+
     #[test]
     fn corner_cases_is_prime() {
         for num in 0u64..30 {
             let is_prime_ref = is_prime64(num);
             let is_safe_prime_ref = is_prime_ref && is_prime64(num / 2);
+            let is_blum_prime_ref = is_prime_ref && num % 4 == 3;
 
             let num_uint = U64::from(num);
 
@@ -289,6 +1124,9 @@ mod tests {
                 is_safe_prime_ref, is_safe_prime_test,
                 "num={num}, expected={is_safe_prime_ref}, actual={is_safe_prime_test}"
             );
+
+            assert_eq!(is_blum_prime_ref, is_prime(Flavor::Blum, &num_uint), "num={num}");
+            assert_eq!(is_blum_prime_ref, fips_is_prime(Flavor::Blum, &num_uint), "num={num}");
         }
     }
 
@@ -320,6 +1158,64 @@ mod tests {
         let _p: U64 = random_prime(&mut rng, Flavor::Safe, 65);
     }
 
+    #[test]
+    fn fallible_generation() {
+        let mut rng = rand::rng();
+        let too_large = Error::BitLengthTooLarge {
+            bit_length: 65,
+            bits_precision: 64,
+        };
+
+        assert_eq!(try_random_prime::<U64, _>(&mut rng, Flavor::Any, 65), Err(too_large));
+        assert_eq!(
+            try_random_prime::<U64, _>(&mut rng, Flavor::Safe, 2),
+            Err(Error::BitLengthTooSmall {
+                bit_length: 2,
+                flavor: Flavor::Safe
+            })
+        );
+        assert_eq!(
+            try_random_prime_uniform::<U64, _>(&mut rng, Flavor::Any, 65),
+            Err(too_large)
+        );
+        assert_eq!(try_random_prime_ct::<{ U64::LIMBS }, _>(&mut rng, 65), Err(too_large));
+        assert_eq!(
+            try_random_prime_congruent(&mut rng, 64, &U64::from(6u32), &U64::from(8u32)),
+            Err(Error::InvalidParameter { name: "residue" })
+        );
+        assert_eq!(
+            try_random_cunningham_chain::<U64, _>(&mut rng, CunninghamKind::First, 0, 64),
+            Err(Error::InvalidParameter { name: "length" })
+        );
+
+        let p: U64 = try_random_prime(&mut rng, Flavor::Any, 64).unwrap();
+        assert!(is_prime64(p.into()));
+        let p: U64 = try_random_prime_ct(&mut rng, 64).unwrap();
+        assert!(is_prime64(p.into()));
+
+        let budget = Budget::unlimited().with_max_candidates(0);
+        assert_eq!(
+            try_random_prime_with_budget::<U64, _>(&mut rng, Flavor::Any, 64, budget),
+            Err(Error::AttemptsExhausted { attempts: 0 })
+        );
+        let deadline = || true;
+        let budget = Budget::unlimited().with_deadline(&deadline);
+        assert_eq!(
+            try_random_cunningham_chain_with_budget::<U64, _>(&mut rng, CunninghamKind::First, 2, 63, budget),
+            Err(Error::AttemptsExhausted { attempts: 0 })
+        );
+        assert_eq!(
+            random_prime_in_range_with_budget(
+                &mut rng,
+                Flavor::Any,
+                &U64::from(1000u32),
+                &U64::from(100000u32),
+                budget
+            ),
+            Err(Error::AttemptsExhausted { attempts: 0 })
+        );
+    }
+
     fn is_prime_ref(num: Word) -> bool {
         num_prime::nt_funcs::is_prime(&num, None).probably()
     }
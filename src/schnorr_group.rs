@@ -0,0 +1,231 @@
+//! Generation of Schnorr groups, that is primes `p = 2kq + 1` with a prime `q` of a given size.
+
+use crypto_bigint::{
+    Monty, NonZero as CTNonZero, Odd, PowBoundedExp, RandomBits, RandomMod, Unsigned, modular::Retrieve,
+};
+use rand_core::CryptoRng;
+
+use crate::{
+    budget::{Budget, BudgetTracker},
+    error::Error,
+    hazmat::{CongruenceSieveFactory, SieveFactory, to_working_precision, zero_with_bit_length},
+    presets::{Flavor, is_prime, try_random_prime_with_budget},
+};
+
+/// The parameters of a Schnorr group generated by [`random_schnorr_group`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchnorrGroup<T> {
+    /// The prime modulus `p = 2kq + 1`.
+    pub p: T,
+    /// The prime order of the subgroup.
+    pub q: T,
+    /// The cofactor multiplier `k`.
+    pub k: T,
+}
+
+impl<T> SchnorrGroup<T>
+where
+    T: Unsigned + RandomMod,
+{
+    /// Returns a random generator of the subgroup of order `q` of the multiplicative group modulo `p`.
+    ///
+    /// The generator is calculated as `h^((p - 1) / q) mod p` for a random `h` in `[2, p - 2]`,
+    /// retrying until the result is not 1, as in the unverifiable generation procedure of FIPS 186-4[^FIPS].
+    ///
+    /// [^FIPS]: FIPS-186.4 standard, Appendix A.2.1,
+    ///   <https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-4.pdf>
+    pub fn generator<R>(&self, rng: &mut R) -> T
+    where
+        R: CryptoRng + ?Sized,
+    {
+        let p = Odd::new(self.p.clone()).expect("`p` is an odd prime");
+        let params = <T as Unsigned>::Monty::new_params_vartime(p.clone());
+        let one = T::one_like(&self.p);
+        let two = T::from_limb_like(2u32.into(), &self.p);
+        let p_minus_one = self.p.wrapping_sub(&one);
+
+        // (p - 1) / q = 2k
+        let exponent = self.k.wrapping_shl_vartime(1);
+        loop {
+            let h = T::random_mod(rng, p.as_nz_ref());
+            if h < two || h >= p_minus_one {
+                continue;
+            }
+            let g = <T as Unsigned>::Monty::new(h, params.clone())
+                .pow_bounded_exp(&exponent, exponent.bits_vartime())
+                .retrieve();
+            if g != one {
+                return g;
+            }
+        }
+    }
+}
+
+/// Returns a random Schnorr group: a prime `p` of size `p_bits` such that `p = 2kq + 1`,
+/// where `q` is a random prime of size `q_bits`.
+///
+/// Compared to safe primes (which correspond to `k = 1`), such groups are much faster to generate,
+/// and are suitable for Diffie-Hellman key exchange and Schnorr or DSA signatures,
+/// as long as only the elements of the subgroup of order `q` are used
+/// (see [`SchnorrGroup::generator`]).
+///
+/// The prime `q` is found with [`try_random_prime_with_budget`](`crate::try_random_prime_with_budget`)
+/// (with an unlimited budget, or the one passed to [`random_schnorr_group_with_budget`]),
+/// and `p` is found by sieving the numbers equal to 1 modulo `2q` with [`CongruenceSieveFactory`]
+/// (picking a new `q` if the sieve is exhausted).
+/// The candidates are checked with [`is_prime`].
+///
+/// Returns an error if `q_bits < 3` (so that `q` is odd), if `p_bits < q_bits + 2`,
+/// or if `p_bits` is larger than the precision of `T` (for fixed-size integers).
+pub fn random_schnorr_group<T, R>(rng: &mut R, p_bits: u32, q_bits: u32) -> Result<SchnorrGroup<T>, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    random_schnorr_group_with_budget(rng, p_bits, q_bits, Budget::unlimited())
+}
+
+/// Same as [`random_schnorr_group`], but returns [`Error::AttemptsExhausted`]
+/// if the limits set by `budget` were reached during the search for `q` or for `p`.
+///
+/// The same `budget` is used for every search for `q` and for the search for `p`,
+/// and its limits apply to each of them separately.
+/// For the search for `p`, every new `q` counts as a new sieve.
+pub fn random_schnorr_group_with_budget<T, R>(
+    rng: &mut R,
+    p_bits: u32,
+    q_bits: u32,
+    budget: Budget<'_>,
+) -> Result<SchnorrGroup<T>, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    // `q` must be odd, otherwise the subgroup of order `q = 2` is just `{1, p - 1}`.
+    if q_bits < 3 {
+        return Err(Error::InvalidParameter { name: "q_bits" });
+    }
+    // `p = 2kq + 1` needs at least one bit more than `2q`.
+    if p_bits < q_bits + 2 {
+        return Err(Error::InvalidParameter { name: "p_bits" });
+    }
+
+    // Check that the result will fit in `T`.
+    zero_with_bit_length::<T>(p_bits)?;
+
+    // If `p_bits` is close to `q_bits`, there are only a few possible values of `k` for the given `q`,
+    // and none of them may produce a prime; in that case, we pick another `q`.
+    let mut tracker = BudgetTracker::new(budget);
+    let (p, q) = 'search: loop {
+        tracker.add_sieve()?;
+        let q = try_random_prime_with_budget::<T, R>(rng, Flavor::Any, q_bits, budget)?;
+        let q = to_working_precision(&q, p_bits)?;
+
+        // Sieve from a random starting point up to `2^p_bits - 1`.
+        let mut factory = CongruenceSieveFactory::new(p_bits, T::one_like(&q), q.wrapping_shl_vartime(1))?;
+        let sieve = factory
+            .make_sieve(rng, None)?
+            .expect("`CongruenceSieveFactory` always produces a sieve");
+        for candidate in sieve {
+            tracker.add_candidate()?;
+            if is_prime(Flavor::Any, &candidate) {
+                break 'search (candidate, q);
+            }
+        }
+    };
+
+    let two_q = q.wrapping_shl_vartime(1);
+    let k = p.wrapping_sub(&T::one_like(&p)) / CTNonZero::new(two_q).expect("`q` is a prime and therefore non-zero");
+
+    Ok(SchnorrGroup { p, q, k })
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto_bigint::{BoxedUint, Monty, Odd, PowBoundedExp, RandomMod, U128, U1024, Unsigned, modular::Retrieve};
+
+    use super::{SchnorrGroup, random_schnorr_group, random_schnorr_group_with_budget};
+    use crate::{Budget, Error, Flavor, is_prime};
+
+    fn check_group<T: Unsigned + RandomMod>(group: &SchnorrGroup<T>, p_bits: u32, q_bits: u32) {
+        let SchnorrGroup { p, q, k } = group;
+        assert_eq!(p.bits_vartime(), p_bits);
+        assert_eq!(q.bits_vartime(), q_bits);
+        assert!(is_prime(Flavor::Any, p));
+        assert!(is_prime(Flavor::Any, q));
+
+        let one = T::one_like(p);
+        assert_eq!(&k.wrapping_mul(q).wrapping_shl_vartime(1).wrapping_add(&one), p);
+
+        // The generator has the order `q`.
+        let mut rng = rand::rng();
+        let g = group.generator(&mut rng);
+        assert_ne!(g, one);
+        let p_odd = Odd::new(p.clone()).unwrap();
+        let params = <T as Unsigned>::Monty::new_params_vartime(p_odd);
+        let g_q = <T as Unsigned>::Monty::new(g, params).pow_bounded_exp(q, q.bits_vartime());
+        assert_eq!(g_q.retrieve(), one);
+    }
+
+    #[test]
+    fn schnorr_groups() {
+        let mut rng = rand::rng();
+        for (p_bits, q_bits) in [(128, 64), (128, 126), (64, 3), (128, 20)] {
+            let group = random_schnorr_group::<U128, _>(&mut rng, p_bits, q_bits).unwrap();
+            check_group(&group, p_bits, q_bits);
+        }
+
+        let group = random_schnorr_group::<U1024, _>(&mut rng, 1024, 160).unwrap();
+        check_group(&group, 1024, 160);
+    }
+
+    #[test]
+    fn schnorr_groups_boxed() {
+        let mut rng = rand::rng();
+        let group = random_schnorr_group::<BoxedUint, _>(&mut rng, 512, 160).unwrap();
+        check_group(&group, 512, 160);
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let mut rng = rand::rng();
+        for q_bits in [1, 2] {
+            assert_eq!(
+                random_schnorr_group::<U128, _>(&mut rng, 128, q_bits).unwrap_err(),
+                Error::InvalidParameter { name: "q_bits" }
+            );
+        }
+        assert_eq!(
+            random_schnorr_group::<U128, _>(&mut rng, 65, 64).unwrap_err(),
+            Error::InvalidParameter { name: "p_bits" }
+        );
+        assert_eq!(
+            random_schnorr_group::<U128, _>(&mut rng, 256, 160).unwrap_err(),
+            Error::BitLengthTooLarge {
+                bit_length: 256,
+                bits_precision: 128
+            }
+        );
+    }
+
+    #[test]
+    fn budget() {
+        let mut rng = rand::rng();
+        let budget = Budget::unlimited().with_max_candidates(0);
+        assert_eq!(
+            random_schnorr_group_with_budget::<U128, _>(&mut rng, 128, 64, budget).unwrap_err(),
+            Error::AttemptsExhausted { attempts: 0 }
+        );
+
+        let deadline = || true;
+        let budget = Budget::unlimited().with_deadline(&deadline);
+        assert_eq!(
+            random_schnorr_group_with_budget::<U128, _>(&mut rng, 128, 64, budget).unwrap_err(),
+            Error::AttemptsExhausted { attempts: 0 }
+        );
+
+        let budget = Budget::unlimited().with_max_sieves(100);
+        let group = random_schnorr_group_with_budget::<U128, _>(&mut rng, 128, 64, budget).unwrap();
+        check_group(&group, 128, 64);
+    }
+}
@@ -0,0 +1,261 @@
+//! Strong prime generation with Gordon's algorithm.
+
+use crypto_bigint::{Monty, Odd, PowBoundedExp, RandomBits, RandomMod, Unsigned, modular::Retrieve};
+use rand_core::CryptoRng;
+
+use crate::{
+    budget::Budget,
+    error::Error,
+    generic::sieve_and_find_with_budget,
+    hazmat::{CongruenceSieveFactory, to_working_precision},
+    presets::{Flavor, is_prime, try_random_prime_with_budget},
+};
+
+/// A strong prime generated by [`random_strong_prime`], along with the auxiliary primes used to construct it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrongPrime<T> {
+    /// The strong prime `p`.
+    pub prime: T,
+    /// The prime dividing `p - 1`.
+    pub r: T,
+    /// The prime dividing `p + 1`.
+    pub s: T,
+    /// The prime dividing `r - 1`.
+    pub t: T,
+}
+
+/// Returns a random strong prime `p` of size `bit_length` generated with Gordon's algorithm[^Gordon].
+///
+/// A strong prime is a prime such that `p - 1` has a large prime factor `r`,
+/// `p + 1` has a large prime factor `s`, and `r - 1` has a large prime factor `t`.
+/// Such primes are required by some legacy standards (e.g. ANSI X9.31) for RSA moduli.
+///
+/// The algorithm works as follows:
+/// - random primes `s` and `t` of sizes `s_bits` and `t_bits` are found with
+///   [`try_random_prime_with_budget`](`crate::try_random_prime_with_budget`)
+///   (with an unlimited budget, or the one passed to [`random_strong_prime_with_budget`]);
+/// - a prime `r = 2kt + 1` of size `r_bits` is found by sieving consecutive values of `k` starting from a random one
+///   with [`CongruenceSieveFactory`];
+/// - `p0 = 2 * (s^(r-2) mod r) * s - 1` is calculated, so that `p0 = 1 mod r` and `p0 = -1 mod s`;
+/// - a prime `p = p0 + 2jrs` of size `bit_length` is found by sieving consecutive values of `j`
+///   starting from a random one in the same way.
+///
+/// The candidates are checked with [`is_prime`].
+///
+/// Returns an error if `s_bits` or `t_bits` are smaller than 2, if `r_bits < t_bits + 2`,
+/// if `bit_length < r_bits + s_bits + 2`,
+/// or if `bit_length` is larger than the precision of `T` (for fixed-size integers).
+/// If `r_bits` is close to `t_bits` (or `bit_length` to `r_bits + s_bits`), there are only a few candidates
+/// for `r` (or `p`), and none of them may be prime; in that case [`Error::NoPrimesInRange`] is returned.
+///
+/// [^Gordon]: J. Gordon, "Strong primes are easy to find",
+///   Advances in Cryptology - EUROCRYPT '84, LNCS 209, 216-223 (1985),
+///   DOI: [10.1007/3-540-39757-4_19](https://doi.org/10.1007/3-540-39757-4_19)
+pub fn random_strong_prime<T, R>(
+    rng: &mut R,
+    bit_length: u32,
+    r_bits: u32,
+    s_bits: u32,
+    t_bits: u32,
+) -> Result<StrongPrime<T>, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    random_strong_prime_with_budget(rng, bit_length, r_bits, s_bits, t_bits, Budget::unlimited())
+}
+
+/// Same as [`random_strong_prime`], but returns [`Error::AttemptsExhausted`]
+/// if the limits set by `budget` were reached during the search for any of the primes.
+///
+/// The same `budget` is used for the searches for `t`, `r`, `s` and `p`,
+/// and its limits apply to each of them separately.
+pub fn random_strong_prime_with_budget<T, R>(
+    rng: &mut R,
+    bit_length: u32,
+    r_bits: u32,
+    s_bits: u32,
+    t_bits: u32,
+    budget: Budget<'_>,
+) -> Result<StrongPrime<T>, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    if t_bits < 2 {
+        return Err(Error::InvalidParameter { name: "t_bits" });
+    }
+    if s_bits < 2 {
+        return Err(Error::InvalidParameter { name: "s_bits" });
+    }
+    // `r = 2kt + 1` needs at least one bit more than `2t`.
+    if r_bits < t_bits + 2 {
+        return Err(Error::InvalidParameter { name: "r_bits" });
+    }
+    // `p = p0 + 2jrs` needs at least one bit more than `2rs`.
+    if bit_length < r_bits + s_bits + 2 {
+        return Err(Error::InvalidParameter { name: "bit_length" });
+    }
+
+    let t = try_random_prime_with_budget::<T, R>(rng, Flavor::Any, t_bits, budget)?;
+    let t = to_working_precision(&t, bit_length)?;
+    let one = T::one_like(&t);
+
+    // r = 2kt + 1
+    let r = random_prime_in_progression(rng, r_bits, one.clone(), t.wrapping_shl_vartime(1), budget)?;
+
+    // `r` and `s` must be different for `2rs` to be the period of the residue class of `p0`.
+    let s = loop {
+        let s = try_random_prime_with_budget::<T, R>(rng, Flavor::Any, s_bits, budget)?;
+        let s = to_working_precision(&s, bit_length)?;
+        if s != r {
+            break s;
+        }
+    };
+
+    // p0 = 2 * (s^(r-2) mod r) * s - 1
+    // Since `r` is prime, `s^(r-2)` is the inverse of `s` modulo `r`.
+    let r_odd = Odd::new(r.clone()).expect("`r` is an odd prime");
+    let params = <T as Unsigned>::Monty::new_params_vartime(r_odd.clone());
+    let s_mod_r = <T as Unsigned>::Monty::new(s.clone() % r_odd.as_nz_ref(), params);
+    let exponent = r.wrapping_sub(&T::from_limb_like(2u32.into(), &r));
+    let s_inv = s_mod_r.pow_bounded_exp(&exponent, exponent.bits_vartime());
+    // Note that the multiplication by 2 happens outside of the modulo, which makes `p0` odd.
+    let u = s_inv.retrieve().wrapping_shl_vartime(1);
+    let p0 = u.wrapping_mul(&s).wrapping_sub(&one);
+
+    // p = p0 + 2jrs
+    let two_r_s = r.wrapping_mul(&s).wrapping_shl_vartime(1);
+    let prime = random_prime_in_progression(rng, bit_length, p0, two_r_s, budget)?;
+
+    Ok(StrongPrime { prime, r, s, t })
+}
+
+/// Returns a random prime of size `bit_length` equal to `residue` modulo `modulus`,
+/// by sieving the progression starting from a random element.
+///
+/// Assumes that `residue < modulus`, that they are coprime, and that `lcm(2, modulus) < 2^(bit_length - 1)`.
+///
+/// If `bit_length` is close to the size of `modulus`, the progression contains only a few numbers
+/// of size `bit_length`, and none of them may be prime; in that case [`Error::NoPrimesInRange`] is returned.
+fn random_prime_in_progression<T, R>(
+    rng: &mut R,
+    bit_length: u32,
+    residue: T,
+    modulus: T,
+    budget: Budget<'_>,
+) -> Result<T, Error>
+where
+    T: Unsigned + RandomBits + RandomMod,
+    R: CryptoRng + ?Sized,
+{
+    let factory = CongruenceSieveFactory::new(bit_length, residue, modulus)?;
+    sieve_and_find_with_budget(rng, factory, |_rng, candidate| is_prime(Flavor::Any, candidate), budget)?
+        .ok_or(Error::NoPrimesInRange { flavor: Flavor::Any })
+}
+
+#[cfg(test)]
+mod tests {
+    use crypto_bigint::{BoxedUint, NonZero, RandomMod, U128, U1024, Unsigned};
+
+    use super::{StrongPrime, random_strong_prime, random_strong_prime_with_budget};
+    use crate::{Budget, Error, Flavor, is_prime};
+
+    fn check_strong_prime<T: Unsigned + RandomMod>(
+        strong_prime: &StrongPrime<T>,
+        bit_length: u32,
+        r_bits: u32,
+        s_bits: u32,
+        t_bits: u32,
+    ) {
+        let StrongPrime { prime, r, s, t } = strong_prime;
+        assert_eq!(prime.bits_vartime(), bit_length);
+        assert_eq!(r.bits_vartime(), r_bits);
+        assert_eq!(s.bits_vartime(), s_bits);
+        assert_eq!(t.bits_vartime(), t_bits);
+        for p in [prime, r, s, t] {
+            assert!(is_prime(Flavor::Any, p));
+        }
+
+        let one = T::one_like(prime);
+        let is_divisible = |x: T, y: &T| bool::from((x % NonZero::new(y.clone()).unwrap()).is_zero());
+        assert!(is_divisible(prime.wrapping_sub(&one), r));
+        assert!(is_divisible(prime.wrapping_add(&one), s));
+        assert!(is_divisible(r.wrapping_sub(&one), t));
+    }
+
+    #[test]
+    fn strong_primes() {
+        let mut rng = rand::rng();
+        for (bit_length, r_bits, s_bits, t_bits) in [(64, 24, 20, 16), (128, 60, 50, 40), (128, 4, 2, 2)] {
+            let p = random_strong_prime::<U128, _>(&mut rng, bit_length, r_bits, s_bits, t_bits).unwrap();
+            check_strong_prime(&p, bit_length, r_bits, s_bits, t_bits);
+        }
+
+        let p = random_strong_prime::<U1024, _>(&mut rng, 1024, 300, 250, 200).unwrap();
+        check_strong_prime(&p, 1024, 300, 250, 200);
+    }
+
+    #[test]
+    fn strong_primes_boxed() {
+        let mut rng = rand::rng();
+        let p = random_strong_prime::<BoxedUint, _>(&mut rng, 512, 200, 150, 100).unwrap();
+        check_strong_prime(&p, 512, 200, 150, 100);
+    }
+
+    #[test]
+    fn few_candidates() {
+        // There are only a few candidates for `r` and `p` of these sizes,
+        // so the search either succeeds or reports that there are no primes instead of looping forever.
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            match random_strong_prime::<U128, _>(&mut rng, 12, 5, 5, 2) {
+                Ok(p) => check_strong_prime(&p, 12, 5, 5, 2),
+                Err(err) => assert_eq!(err, Error::NoPrimesInRange { flavor: Flavor::Any }),
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let mut rng = rand::rng();
+        assert_eq!(
+            random_strong_prime::<U128, _>(&mut rng, 128, 60, 50, 1).unwrap_err(),
+            Error::InvalidParameter { name: "t_bits" }
+        );
+        assert_eq!(
+            random_strong_prime::<U128, _>(&mut rng, 128, 60, 1, 40).unwrap_err(),
+            Error::InvalidParameter { name: "s_bits" }
+        );
+        assert_eq!(
+            random_strong_prime::<U128, _>(&mut rng, 128, 41, 50, 40).unwrap_err(),
+            Error::InvalidParameter { name: "r_bits" }
+        );
+        assert_eq!(
+            random_strong_prime::<U128, _>(&mut rng, 111, 60, 50, 40).unwrap_err(),
+            Error::InvalidParameter { name: "bit_length" }
+        );
+        assert_eq!(
+            random_strong_prime::<U128, _>(&mut rng, 256, 60, 50, 40).unwrap_err(),
+            Error::BitLengthTooLarge {
+                bit_length: 256,
+                bits_precision: 128
+            }
+        );
+    }
+
+    #[test]
+    fn budget() {
+        let mut rng = rand::rng();
+        let budget = Budget::unlimited().with_max_candidates(0);
+        assert_eq!(
+            random_strong_prime_with_budget::<U128, _>(&mut rng, 128, 60, 50, 40, budget).unwrap_err(),
+            Error::AttemptsExhausted { attempts: 0 }
+        );
+
+        // The limits apply to each of the searches separately.
+        let budget = Budget::unlimited().with_max_sieves(100);
+        let p = random_strong_prime_with_budget::<U128, _>(&mut rng, 128, 60, 50, 40, budget).unwrap();
+        check_strong_prime(&p, 128, 60, 50, 40);
+    }
+}